@@ -0,0 +1,28 @@
+use mines::{
+    bot::{Blind, Bot, Simple},
+    tourney,
+};
+
+fn main() {
+    let args: Vec<u64> = std::env::args()
+        .skip(1)
+        .filter_map(|a| a.parse::<u64>().ok())
+        .collect();
+    // args: [games, base_seed]
+    let games = args.first().copied().unwrap_or(200);
+    let base = args.get(1).copied().unwrap_or(0);
+    let seeds: Vec<u64> = (base..base + games).collect();
+
+    let bots: [&dyn Bot; 2] = [&Blind, &Simple];
+    for (name, c, w, h) in [
+        ("beginner", 10, 9, 9),
+        ("intermediate", 40, 16, 16),
+        ("expert", 99, 30, 16),
+    ] {
+        println!("== {name} {w}x{h}/{c} ==");
+        match tourney::run(&bots, c, w, h, &seeds) {
+            Ok(ls) => println!("{}", tourney::format_table(&ls)),
+            Err(e) => eprintln!("{e}"),
+        }
+    }
+}
@@ -1,20 +1,46 @@
 use std::io::{stdin, stdout, Write};
 
-use mines::{location::Loc, mmap::MineMap};
+use mines::{
+    difficulty::{validate_with, Difficulty},
+    location::Loc,
+    mmap::{MineMap, Safety},
+};
 // use rand::{seq::SliceRandom, thread_rng, Rng};
 // use std::fmt::Write;
 // use smallvec::SmallVec;
 
+const ARGS: &str = "args: <beginner|intermediate|expert> | <width height count> [--safety none|cell|opening|<radius>]";
 const TIP: &str = "input: <do x y>\ndo: r  reveal\n    f  flag\n    a  reveal around\n    c  count around\n    q  quit";
 
+fn parse() -> Result<(MineMap, Safety), String> {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let mut safety = Safety::default();
+    if let Some(i) = args.iter().position(|a| a == "--safety") {
+        let v = args.get(i + 1).ok_or("缺少参数值：--safety")?;
+        safety = v.parse()?;
+        args.drain(i..i + 2);
+    }
+    let difficulty = match &args[..] {
+        [d] => d.parse::<Difficulty>()?,
+        [w, h, c] => match (w.parse(), h.parse(), c.parse()) {
+            (Ok(w), Ok(h), Ok(c)) => Difficulty::of(w, h, c),
+            _ => return Err("宽、高、雷数须为整数！".to_string()),
+        },
+        _ => return Err("参数数量不正确！".to_string()),
+    };
+    let (w, h, c) = difficulty.size();
+    validate_with(w, h, c, safety)?;
+    Ok((MineMap::new(c, w, h)?, safety))
+}
+
 fn main() {
-    let args: Vec<u16> = std::env::args()
-        .filter_map(|a| a.parse::<u16>().ok())
-        .collect();
-    let [w, h, c] = args[..] else {
-        panic!("args: <width, height, count>")
+    let (mut mines, safety) = match parse() {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln!("{e}\n{ARGS}");
+            std::process::exit(2);
+        }
     };
-    let mut mines = MineMap::new(c, w as u8, h as u8).unwrap();
 
     println!("{TIP}");
 
@@ -47,9 +73,9 @@ fn main() {
             }
             "r" => {
                 if flag == 0 {
-                    mines.new_game(Some(Loc(x as u8, y as u8)));
+                    mines.new_game_with(Some(Loc(x as u8, y as u8)), safety, None);
                     println!("{}", mines.format_str());
-                    mines.reveal_around(x, y);
+                    mines.reveal(x, y);
                     flag += 1;
                 } else {
                     mines.reveal(x, y);
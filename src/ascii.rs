@@ -0,0 +1,95 @@
+//! # 字符画布局
+//! 每行表示地图的一行，行内空白忽略，空行与`#`开头的行忽略：
+//! - `*` 地雷（未打开）
+//! - `F` 插旗的地雷
+//! - `X` 已打开（踩中）的地雷
+//! - `.` 未打开的非雷单元格
+//! - `f` 插旗的非雷单元格，即错误的标记
+//! - `0`~`8` 已打开的单元格，数字须与周围地雷数一致
+//!
+//! ```text
+//! # 3x3，一颗地雷
+//! 1 1 .
+//! F 1 .
+//! 1 1 .
+//! ```
+
+use std::str::FromStr;
+
+use crate::{cell::Cell, location::Loc, mmap::MineMap};
+
+/// 解析字符画
+pub fn parse(text: &str) -> Result<MineMap, String> {
+    let rows: Vec<Vec<char>> = text
+        .lines()
+        .map(|ln| ln.trim())
+        .filter(|ln| !ln.is_empty() && !ln.starts_with('#'))
+        .map(|ln| ln.chars().filter(|c| !c.is_whitespace()).collect())
+        .collect();
+    let height = rows.len();
+    let width = rows.first().map_or(0, |r| r.len());
+    if width > u8::MAX as usize || height > u8::MAX as usize {
+        return Err(format!("区域过大：{width}x{height}"));
+    }
+    let mut mines = Vec::new();
+    for (y, row) in rows.iter().enumerate() {
+        if row.len() != width {
+            return Err(format!("第{}行有{}列，应为{width}列！", y + 1, row.len()));
+        }
+        for (x, &ch) in row.iter().enumerate() {
+            match ch {
+                '*' | 'F' | 'X' => mines.push(y * width + x),
+                '.' | 'f' | '0'..='8' => {}
+                _ => return Err(format!("无法识别的字符`{ch}`：{}", Loc::from(x, y))),
+            }
+        }
+    }
+    let mut mm = MineMap::by_mines(width as u8, height as u8, &mines)?;
+    for (i, &ch) in rows.iter().flatten().enumerate() {
+        let mut c = Cell(mm.map[i]);
+        match ch {
+            'F' | 'f' => c.switch_flag(),
+            'X' => c.reveal(),
+            '0'..='8' => {
+                let v = ch as u8 - b'0';
+                if v != c.get_warn() {
+                    return Err(format!(
+                        "数字与周围地雷数不符：{} 为{v}，实际{}",
+                        Loc::from(i % width, i / width),
+                        c.get_warn()
+                    ));
+                }
+                c.reveal();
+            }
+            _ => continue,
+        }
+        mm.map[i] = c.0;
+    }
+    Ok(mm)
+}
+
+/// 输出字符画，格式见模块说明，可由`parse`还原
+/// - 对局失败后全部地雷均已打开，其中插旗的仍输出为`F`，还原后不再是打开状态
+pub fn format(map: &MineMap) -> String {
+    let w = map.width as usize;
+    let mut buf = String::with_capacity(map.map.len() * 2);
+    for (i, c) in map.iter().enumerate() {
+        buf.push(match (c.is_mine(), c.is_reveal(), c.is_flagged()) {
+            (true, true, false) => 'X',
+            (true, _, true) => 'F',
+            (true, false, false) => '*',
+            (false, true, _) => (b'0' + c.get_warn()) as char,
+            (false, false, true) => 'f',
+            (false, false, false) => '.',
+        });
+        buf.push(if (i + 1) % w == 0 { '\n' } else { ' ' });
+    }
+    buf
+}
+
+impl FromStr for MineMap {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, String> {
+        parse(s)
+    }
+}
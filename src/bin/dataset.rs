@@ -0,0 +1,110 @@
+use std::{
+    fs::{self, File},
+    io::BufWriter,
+    path::PathBuf,
+};
+
+use mines::dataset::{self, Sample};
+
+const TIP: &str = "args: [--games N] [--seed BASE] [--shard I --shards N] [--format bin|csv]
+      [--width W --height H --count C] [--max-depth D] [--out DIR]";
+
+struct Opts {
+    games: u64,
+    seed: u64,
+    shard: u64,
+    shards: u64,
+    csv: bool,
+    width: u8,
+    height: u8,
+    count: u16,
+    max_depth: u32,
+    out: PathBuf,
+}
+
+fn parse() -> Result<Opts, String> {
+    let mut o = Opts {
+        games: 1000,
+        seed: 0,
+        shard: 0,
+        shards: 1,
+        csv: false,
+        width: 30,
+        height: 16,
+        count: 99,
+        max_depth: 64,
+        out: PathBuf::from("."),
+    };
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    for kv in args.chunks(2) {
+        let [k, v] = kv else {
+            return Err(format!("缺少参数值：{}", kv[0]));
+        };
+        let bad = |_| format!("无效参数：{k} {v}");
+        match k.as_str() {
+            "--games" => o.games = v.parse().map_err(bad)?,
+            "--seed" => o.seed = v.parse().map_err(bad)?,
+            "--shard" => o.shard = v.parse().map_err(bad)?,
+            "--shards" => o.shards = v.parse().map_err(bad)?,
+            "--width" => o.width = v.parse().map_err(bad)?,
+            "--height" => o.height = v.parse().map_err(bad)?,
+            "--count" => o.count = v.parse().map_err(bad)?,
+            "--max-depth" => o.max_depth = v.parse().map_err(bad)?,
+            "--out" => o.out = PathBuf::from(v),
+            "--format" => match v.as_str() {
+                "bin" => o.csv = false,
+                "csv" => o.csv = true,
+                _ => return Err(format!("无效格式：{v}")),
+            },
+            _ => return Err(format!("未知参数：{k}")),
+        }
+    }
+    if o.shards == 0 || o.shard >= o.shards {
+        return Err("分片序号须小于分片数！".to_string());
+    }
+    Ok(o)
+}
+
+fn main() {
+    let o = match parse() {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln!("{e}\n{TIP}");
+            std::process::exit(2);
+        }
+    };
+    let ext = if o.csv { "csv" } else { "bin" };
+    let path = o
+        .out
+        .join(format!("shard-{:04}-of-{:04}.{ext}", o.shard, o.shards));
+    let run = || -> std::io::Result<(u64, u64)> {
+        fs::create_dir_all(&o.out)?;
+        let mut out = BufWriter::new(File::create(&path)?);
+        if o.csv {
+            use std::io::Write;
+            writeln!(out, "{}", Sample::CSV_HEADER)?;
+        }
+        let (mut ok, mut skip) = (0, 0);
+        // 对局序号按分片数取模分配，种子只与序号有关，分片方式不影响样本
+        for i in (o.shard..o.games).step_by(o.shards as usize) {
+            let seed = dataset::derive_seed(o.seed, i);
+            match dataset::sample(seed, o.count, o.width, o.height, o.max_depth) {
+                Some(s) if o.csv => s.write_csv(&mut out)?,
+                Some(s) => s.write_bin(&mut out)?,
+                None => {
+                    skip += 1;
+                    continue;
+                }
+            }
+            ok += 1;
+        }
+        Ok((ok, skip))
+    };
+    match run() {
+        Ok((ok, skip)) => println!("{}: {ok} samples, {skip} skipped", path.display()),
+        Err(e) => {
+            eprintln!("{}: {e}", path.display());
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,212 @@
+//! # 联机竞速参考客户端
+//! 逐行输入操作，收到的消息在后台线程中打印。
+
+use std::{
+    io::{stdin, stdout, Write},
+    net::TcpStream,
+    thread,
+};
+
+use mines::{
+    game::{Action, Status},
+    location::Loc,
+    mmap::MineMap,
+    render::{Plain, Renderer},
+    wire::{self, Msg},
+};
+
+const TIP: &str = "args: [--name NAME] [--addr HOST:PORT]";
+const HELP: &str = "input: <do x y>\ndo: r  reveal\n    f  flag\n    c  chord\n    q  quit";
+
+/// 后台线程维护的比赛信息
+#[derive(Default)]
+struct Board {
+    width: u8,
+    height: u8,
+    names: Vec<String>,
+    /// 每名玩家的进度与结果
+    marks: Vec<String>,
+}
+
+impl Board {
+    fn name(&self, id: u8) -> &str {
+        self.names.get(id as usize).map_or("?", |s| s.as_str())
+    }
+
+    fn mark(&mut self, id: u8, s: String) {
+        if let Some(m) = self.marks.get_mut(id as usize) {
+            *m = s;
+        }
+    }
+
+    fn scores(&self) -> String {
+        self.names
+            .iter()
+            .zip(&self.marks)
+            .map(|(n, m)| format!("{n} {m}"))
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+
+    /// 处理一条消息
+    /// # Returns
+    /// - 比赛是否结束
+    fn handle(&mut self, me: &mut u8, msg: Msg) -> bool {
+        match msg {
+            Msg::Welcome { id, need } => {
+                *me = id;
+                println!("joined as #{id}, waiting for {need} players...");
+            }
+            Msg::Start {
+                width,
+                height,
+                count,
+                start,
+                players,
+            } => {
+                (self.width, self.height) = (width, height);
+                self.marks = vec!["0%".to_string(); players.len()];
+                self.names = players.into_iter().map(|(_, n)| n).collect();
+                println!("start! {width}x{height}/{count}, opened at {start}\n{HELP}");
+            }
+            Msg::View { status, cells } => {
+                let mut raw = vec![self.width, self.height];
+                raw.extend(cells);
+                if let Ok(map) = MineMap::by(raw, true) {
+                    let text = Plain {
+                        labels: true,
+                        ..Default::default()
+                    }
+                    .render(&map);
+                    print!("{text}");
+                }
+                match status {
+                    Status::Won => println!("you win!"),
+                    Status::Lost => println!("boom!"),
+                    _ => {}
+                }
+            }
+            Msg::Progress { id, percent } => {
+                self.mark(id, format!("{percent}%"));
+                println!("{}", self.scores());
+            }
+            Msg::Dead { id, ms } => {
+                self.mark(id, format!("dead {:.1}s", ms as f64 / 1000.0));
+                println!("{} hit a mine\n{}", self.name(id), self.scores());
+            }
+            Msg::Finish { id, ms } => {
+                self.mark(id, format!("done {:.3}s", ms as f64 / 1000.0));
+                println!("{} finished\n{}", self.name(id), self.scores());
+            }
+            Msg::Left { id } => {
+                self.mark(id, "left".to_string());
+                println!("{} left", self.name(id));
+            }
+            Msg::End { standings } => {
+                println!("== result ==");
+                for (rank, s) in standings.iter().enumerate() {
+                    let r = match s.status {
+                        Status::Won => format!("{:.3}s", s.ms as f64 / 1000.0),
+                        _ => format!("{}%", s.percent),
+                    };
+                    println!("{}. {} {r}", rank + 1, self.name(s.id));
+                }
+                return true;
+            }
+            Msg::Error { msg } => eprintln!("error: {msg}"),
+            Msg::Hello { .. } | Msg::Act(_) => {}
+        }
+        false
+    }
+}
+
+fn parse() -> Result<(String, String), String> {
+    let mut name = std::env::var("USER").unwrap_or_else(|_| "player".to_string());
+    let mut addr = "127.0.0.1:7878".to_string();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    for kv in args.chunks(2) {
+        let [k, v] = kv else {
+            return Err(format!("缺少参数值：{}", kv[0]));
+        };
+        match k.as_str() {
+            "--name" => name = v.clone(),
+            "--addr" => addr = v.clone(),
+            _ => return Err(format!("未知参数：{k}")),
+        }
+    }
+    Ok((name, addr))
+}
+
+fn main() {
+    let (name, addr) = match parse() {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln!("{e}\n{TIP}");
+            std::process::exit(2);
+        }
+    };
+    let mut stream = match TcpStream::connect(&addr) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("无法连接{addr}：{e}");
+            std::process::exit(1);
+        }
+    };
+    if let Err(e) = wire::send(&mut stream, &Msg::Hello { name }) {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
+    let mut reader = stream.try_clone().expect("无法复制连接");
+    thread::spawn(move || {
+        let (mut board, mut me) = (Board::default(), 0);
+        loop {
+            match wire::recv(&mut reader) {
+                Ok(msg) => {
+                    if board.handle(&mut me, msg) {
+                        std::process::exit(0);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("连接已断开：{e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+    });
+
+    let mut input = String::with_capacity(16);
+    loop {
+        input.clear();
+        let _ = stdout().flush();
+        if stdin().read_line(&mut input).unwrap_or(0) == 0 {
+            return;
+        }
+        let parts: Vec<&str> = input.split_whitespace().collect();
+        let action = match parts[..] {
+            ["q"] => return,
+            [o, x, y] => {
+                let (Ok(x), Ok(y)) = (x.parse(), y.parse()) else {
+                    println!("{HELP}");
+                    continue;
+                };
+                match o {
+                    "r" => Action::Reveal(Loc(x, y)),
+                    "f" => Action::Flag(Loc(x, y)),
+                    "c" => Action::Chord(Loc(x, y)),
+                    _ => {
+                        println!("{HELP}");
+                        continue;
+                    }
+                }
+            }
+            [] => continue,
+            _ => {
+                println!("{HELP}");
+                continue;
+            }
+        };
+        if wire::send(&mut stream, &Msg::Act(action)).is_err() {
+            return;
+        }
+    }
+}
@@ -0,0 +1,338 @@
+//! # 联机竞速服务端
+//! 仅监听`127.0.0.1`。等待指定数量的玩家加入后开局，
+//! 所有玩家使用相同的布局，各自独立进行，协议见`mines::wire`。
+//! 全部玩家结束或断开后公布完整布局与名次并退出。
+
+use std::{
+    io::Write,
+    net::{Ipv4Addr, TcpListener, TcpStream},
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+use mines::{
+    game::{Action, Game, Status},
+    location::Loc,
+    mmap::MineMap,
+    wire::{self, Msg, Standing, MAX_PLAYERS, MIN_PLAYERS},
+};
+
+const TIP: &str = "args: [--players N] [--port P] [--width W --height H --count C] [--seed S]";
+
+struct Opts {
+    players: u8,
+    port: u16,
+    width: u8,
+    height: u8,
+    count: u16,
+    seed: Option<u64>,
+}
+
+fn parse() -> Result<Opts, String> {
+    let mut o = Opts {
+        players: 2,
+        port: 7878,
+        width: 16,
+        height: 16,
+        count: 40,
+        seed: None,
+    };
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    for kv in args.chunks(2) {
+        let [k, v] = kv else {
+            return Err(format!("缺少参数值：{}", kv[0]));
+        };
+        let bad = |_| format!("无效参数：{k} {v}");
+        match k.as_str() {
+            "--players" => o.players = v.parse().map_err(bad)?,
+            "--port" => o.port = v.parse().map_err(bad)?,
+            "--width" => o.width = v.parse().map_err(bad)?,
+            "--height" => o.height = v.parse().map_err(bad)?,
+            "--count" => o.count = v.parse().map_err(bad)?,
+            "--seed" => o.seed = Some(v.parse().map_err(bad)?),
+            _ => return Err(format!("未知参数：{k}")),
+        }
+    }
+    if !(MIN_PLAYERS..=MAX_PLAYERS).contains(&o.players) {
+        return Err(format!("玩家数须在{MIN_PLAYERS}~{MAX_PLAYERS}之间！"));
+    }
+    Ok(o)
+}
+
+/// 玩家线程发往主线程的事件
+enum Event {
+    Act(u8, Action),
+    Gone(u8),
+}
+
+struct Player {
+    name: String,
+    stream: TcpStream,
+    game: Game,
+    /// 已断开
+    gone: bool,
+    percent: u8,
+}
+
+impl Player {
+    fn done(&self) -> bool {
+        self.gone || self.game.is_over()
+    }
+
+    fn send(&mut self, msg: &Msg) {
+        if !self.gone {
+            let _ = wire::send(&mut self.stream, msg);
+        }
+    }
+
+    /// 发送玩家视角
+    /// - `layout` 为真时发送完整布局，仅在全部玩家结束后使用，避免先结束的玩家泄露布局
+    /// - 踩雷后只显示踩中的地雷
+    fn view(&mut self, layout: bool) {
+        let game = &self.game;
+        let cells = if layout {
+            game.map().map.clone()
+        } else {
+            let w = game.map().width as usize;
+            let boom = game.boom().map(|Loc(x, y)| y as usize * w + x as usize);
+            let mut cells = game.view();
+            for (i, v) in cells.iter_mut().enumerate() {
+                if *v & 0x1f > 8 && Some(i) != boom {
+                    *v &= 0x40;
+                }
+            }
+            cells
+        };
+        let msg = Msg::View {
+            status: game.status(),
+            cells,
+        };
+        self.send(&msg);
+    }
+}
+
+fn broadcast(players: &mut [Player], msg: &Msg) {
+    for p in players {
+        p.send(msg);
+    }
+}
+
+/// 等待玩家发送`Hello`
+fn hello(mut stream: TcpStream) -> Option<(String, TcpStream)> {
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(10)));
+    let name = match wire::recv(&mut stream) {
+        Ok(Msg::Hello { name }) => name,
+        Ok(_) => {
+            let msg = "请先发送Hello".to_string();
+            let _ = wire::send(&mut stream, &Msg::Error { msg });
+            return None;
+        }
+        Err(e) => {
+            let _ = wire::send(&mut stream, &Msg::Error { msg: e.to_string() });
+            return None;
+        }
+    };
+    let _ = stream.set_read_timeout(None);
+    Some((name, stream))
+}
+
+/// 名次：完成者按用时，其余按进度
+fn standings(players: &[Player]) -> Vec<Standing> {
+    let mut ls: Vec<Standing> = players
+        .iter()
+        .enumerate()
+        .map(|(id, p)| Standing {
+            id: id as u8,
+            status: p.game.status(),
+            ms: if p.game.is_over() {
+                p.game.elapsed().as_millis().min(u32::MAX as u128) as u32
+            } else {
+                0
+            },
+            percent: p.percent,
+        })
+        .collect();
+    ls.sort_by_key(|s| {
+        let won = s.status == Status::Won;
+        (!won, if won { s.ms } else { 0 }, 100 - s.percent)
+    });
+    ls
+}
+
+fn run(o: Opts) -> Result<(), String> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, o.port))
+        .map_err(|e| format!("无法监听端口{}：{e}", o.port))?;
+    if let Ok(addr) = listener.local_addr() {
+        // 首行输出实际地址，便于测试读取系统分配的端口
+        println!("listening on {addr}");
+        let _ = std::io::stdout().flush();
+    }
+
+    let seed = o.seed.unwrap_or_else(rand::random);
+    let start = Loc(o.width / 2, o.height / 2);
+    let mut layout = MineMap::new(o.count, o.width, o.height)?;
+    layout.new_game_by_seed(Some(start), seed);
+    layout.reveal(start.0 as usize, start.1 as usize);
+
+    // 每个连接在各自的线程中等待`Hello`，迟迟不发送的连接不会阻塞其他玩家加入
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let tx = tx.clone();
+            thread::spawn(move || {
+                if let Some(p) = hello(stream) {
+                    let _ = tx.send(p);
+                }
+            });
+        }
+    });
+    let mut joined = Vec::with_capacity(o.players as usize);
+    while joined.len() < o.players as usize {
+        let (name, mut stream) = rx.recv().map_err(|_| "停止监听".to_string())?;
+        let welcome = Msg::Welcome {
+            id: joined.len() as u8,
+            need: o.players,
+        };
+        if wire::send(&mut stream, &welcome).is_err() {
+            continue;
+        }
+        eprintln!("{name} 加入（{}/{}）", joined.len() + 1, o.players);
+        joined.push((name, stream));
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let mut players = Vec::with_capacity(joined.len());
+    for (id, (name, stream)) in joined.into_iter().enumerate() {
+        // 计时从开局算起
+        let game = Game::by_map(layout.clone());
+        let percent = (game.progress() * 100.0) as u8;
+        let reader = stream.try_clone();
+        players.push(Player {
+            name,
+            stream,
+            game,
+            gone: reader.is_err(),
+            percent,
+        });
+        let Ok(mut stream) = reader else {
+            continue;
+        };
+        let tx = tx.clone();
+        let id = id as u8;
+        thread::spawn(move || loop {
+            match wire::recv(&mut stream) {
+                Ok(Msg::Act(a)) => {
+                    if tx.send(Event::Act(id, a)).is_err() {
+                        return;
+                    }
+                }
+                Ok(_) => {}
+                Err(_) => {
+                    let _ = tx.send(Event::Gone(id));
+                    return;
+                }
+            }
+        });
+    }
+    drop(tx);
+    let start_msg = Msg::Start {
+        width: o.width,
+        height: o.height,
+        count: o.count,
+        start,
+        players: players
+            .iter()
+            .enumerate()
+            .map(|(id, p)| (id as u8, p.name.clone()))
+            .collect(),
+    };
+    broadcast(&mut players, &start_msg);
+    for id in 0..players.len() {
+        players[id].view(false);
+        let percent = players[id].percent;
+        broadcast(
+            &mut players,
+            &Msg::Progress {
+                id: id as u8,
+                percent,
+            },
+        );
+    }
+
+    while !players.iter().all(Player::done) {
+        let Ok(ev) = rx.recv() else {
+            break;
+        };
+        match ev {
+            Event::Act(id, action) => {
+                let p = &mut players[id as usize];
+                if p.done() {
+                    continue;
+                }
+                // 无效操作不计数，也无需回应
+                let before = p.game.actions();
+                p.game.act(action);
+                if p.game.actions() == before {
+                    continue;
+                }
+                p.view(false);
+                let game = &p.game;
+                let ms = game.elapsed().as_millis().min(u32::MAX as u128) as u32;
+                let percent = (game.progress() * 100.0) as u8;
+                let status = game.status();
+                let changed = percent != p.percent;
+                p.percent = percent;
+                if changed {
+                    broadcast(&mut players, &Msg::Progress { id, percent });
+                }
+                match status {
+                    Status::Lost => broadcast(&mut players, &Msg::Dead { id, ms }),
+                    Status::Won => broadcast(&mut players, &Msg::Finish { id, ms }),
+                    _ => {}
+                }
+            }
+            Event::Gone(id) => {
+                let p = &mut players[id as usize];
+                if p.gone {
+                    continue;
+                }
+                p.gone = true;
+                eprintln!("{} 断开", p.name);
+                broadcast(&mut players, &Msg::Left { id });
+            }
+        }
+    }
+
+    for p in &mut players {
+        p.view(true);
+    }
+    let standings = standings(&players);
+    for (rank, s) in standings.iter().enumerate() {
+        let p = &players[s.id as usize];
+        eprintln!(
+            "{}. {} {:?} {}% {}ms",
+            rank + 1,
+            p.name,
+            s.status,
+            s.percent,
+            s.ms
+        );
+    }
+    broadcast(&mut players, &Msg::End { standings });
+    Ok(())
+}
+
+fn main() {
+    let o = match parse() {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln!("{e}\n{TIP}");
+            std::process::exit(2);
+        }
+    };
+    if let Err(e) = run(o) {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,174 @@
+use std::{collections::HashMap, fmt::Write, sync::Mutex};
+
+use mines::{
+    cell::Cell,
+    game::{Action, Game, Status},
+    location::Loc,
+    mmap::MineMap,
+};
+
+use crate::http::{self, json_str, Request, Response};
+
+/// 同时保存的对局数上限
+const MAX_SESSIONS: usize = 1024;
+
+/// 内存中的对局，以随机id为键
+#[derive(Default)]
+pub struct Sessions {
+    games: Mutex<HashMap<String, Game>>,
+}
+
+pub fn handle(sessions: &Sessions, req: &Request) -> Response {
+    let parts: Vec<&str> = req.path.split('/').filter(|s| !s.is_empty()).collect();
+    let method = req.method.as_str();
+    if method == "OPTIONS" {
+        return Response::json(204, String::new());
+    }
+    let res = match (method, &parts[..]) {
+        ("POST", ["games"]) => create(sessions, req),
+        (_, ["games"]) => return Response::error(405, "仅支持POST"),
+        (_, ["games", id, rest @ ..]) => {
+            let mut games = sessions.games.lock().unwrap_or_else(|e| e.into_inner());
+            if method == "DELETE" && rest.is_empty() {
+                return match games.remove(*id) {
+                    Some(_) => Response::json(204, String::new()),
+                    None => Response::error(404, "对局不存在"),
+                };
+            }
+            let Some(game) = games.get_mut(*id) else {
+                return Response::error(404, "对局不存在");
+            };
+            match (method, rest) {
+                ("GET", []) => Ok(Response::json(200, view(id, game))),
+                ("GET", ["stats"]) => Ok(Response::json(200, stats(game))),
+                ("POST", [op @ ("reveal" | "flag" | "chord")]) => act(id, game, op, req),
+                _ => return Response::error(405, "不支持的操作"),
+            }
+        }
+        _ => return Response::error(404, "路径不存在"),
+    };
+    res.unwrap_or_else(|e| Response::error(400, &e))
+}
+
+/// 创建对局，参数`width`、`height`、`mines`，可选`seed`
+fn create(sessions: &Sessions, req: &Request) -> Result<Response, String> {
+    let ps = http::params(req)?;
+    let get = |k: &str| ps.get(k).copied().ok_or(format!("缺少参数{k}"));
+    let (w, h, c) = (get("width")?, get("height")?, get("mines")?);
+    if w > u8::MAX as u64 || h > u8::MAX as u64 || c > u16::MAX as u64 {
+        return Err(format!("参数超出范围：{w}x{h}，{c}颗地雷"));
+    }
+    let map = MineMap::new(c as u16, w as u8, h as u8)?;
+    let game = match ps.get("seed") {
+        Some(&seed) => Game::by_seed(map, seed),
+        None => Game::new(map),
+    };
+
+    let mut games = sessions.games.lock().unwrap_or_else(|e| e.into_inner());
+    if games.len() >= MAX_SESSIONS {
+        return Ok(Response::error(503, "对局数已达上限"));
+    }
+    let id = loop {
+        let id = format!("{:016x}", rand::random::<u64>());
+        if !games.contains_key(&id) {
+            break id;
+        }
+    };
+    let body = view(&id, &game);
+    games.insert(id, game);
+    Ok(Response::json(201, body))
+}
+
+/// 执行操作，参数`x`、`y`
+fn act(id: &str, game: &mut Game, op: &str, req: &Request) -> Result<Response, String> {
+    let ps = http::params(req)?;
+    let get = |k: &str| ps.get(k).copied().ok_or(format!("缺少参数{k}"));
+    let (x, y) = (get("x")?, get("y")?);
+    let map = game.map();
+    if x >= map.width as u64 || y >= map.height as u64 {
+        return Err(format!("坐标越界：({x}, {y})"));
+    }
+    let loc = Loc(x as u8, y as u8);
+    let action = match op {
+        "reveal" => Action::Reveal(loc),
+        "flag" => Action::Flag(loc),
+        _ => Action::Chord(loc),
+    };
+    let opened = game.act(action);
+    Ok(Response::json(
+        200,
+        format!("{{\"opened\":{opened},\"game\":{}}}", view(id, game)),
+    ))
+}
+
+fn status_str(s: Status) -> &'static str {
+    match s {
+        Status::Ready => "ready",
+        Status::Playing => "playing",
+        Status::Won => "won",
+        Status::Lost => "lost",
+    }
+}
+
+/// # 玩家视角
+/// 每行一个字符串：`.`未打开，`F`插旗，`0`~`8`已打开。
+/// 对局结束后才显示地雷`*`与插错的旗`X`，种子也仅在结束后给出。
+fn view(id: &str, game: &Game) -> String {
+    let map = game.map();
+    let over = game.is_over();
+    let w = map.width as usize;
+    // 进行中只使用玩家视角的数据，确保不泄露地雷位置
+    let cells: Vec<u8> = if over { map.map.clone() } else { game.view() };
+    let mut buf = String::new();
+    let _ = write!(
+        buf,
+        "{{\"id\":{},\"width\":{},\"height\":{},\"mines\":{},\"flags\":{},\"status\":\"{}\"",
+        json_str(id),
+        map.width,
+        map.height,
+        map.count,
+        map.count_flagged(),
+        status_str(game.status())
+    );
+    if let (true, Some(seed)) = (over, game.seed()) {
+        let _ = write!(buf, ",\"seed\":{seed}");
+    }
+    buf.push_str(",\"board\":[");
+    for (y, row) in cells.chunks(w).enumerate() {
+        if y > 0 {
+            buf.push(',');
+        }
+        buf.push('"');
+        for &v in row {
+            let c = Cell(v);
+            buf.push(match (c.is_flagged(), c.is_reveal(), c.is_mine() && over) {
+                (true, _, false) if over => 'X',
+                (true, _, _) => 'F',
+                (_, _, true) => '*',
+                (_, true, _) => (b'0' + c.get_warn()) as char,
+                _ => '.',
+            });
+        }
+        buf.push('"');
+    }
+    buf.push_str("]}");
+    buf
+}
+
+fn stats(game: &Game) -> String {
+    let mut buf = String::new();
+    let _ = write!(
+        buf,
+        "{{\"status\":\"{}\",\"elapsed_ms\":{},\"actions\":{},\"progress\":{}",
+        status_str(game.status()),
+        game.elapsed().as_millis(),
+        game.actions(),
+        game.progress()
+    );
+    // 3BV依赖完整布局，结束后才给出
+    if game.is_over() {
+        let _ = write!(buf, ",\"3bv\":{}", game.map().count_3bv());
+    }
+    buf.push('}');
+    buf
+}
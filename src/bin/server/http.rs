@@ -0,0 +1,160 @@
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::TcpStream,
+};
+
+/// 请求头总长度上限
+const MAX_HEAD: usize = 8 * 1024;
+/// 请求体长度上限
+const MAX_BODY: usize = 4 * 1024;
+
+pub struct Request {
+    pub method: String,
+    /// 不含查询参数的路径
+    pub path: String,
+    pub query: String,
+    pub body: String,
+}
+
+pub struct Response {
+    pub status: u16,
+    /// JSON
+    pub body: String,
+}
+
+impl Response {
+    pub fn json(status: u16, body: String) -> Self {
+        Self { status, body }
+    }
+
+    pub fn error(status: u16, msg: &str) -> Self {
+        Self {
+            status,
+            body: format!("{{\"error\":{}}}", json_str(msg)),
+        }
+    }
+}
+
+/// 读取一个请求，仅支持`Content-Length`指定长度的请求体
+pub fn read_request(stream: &TcpStream) -> io::Result<Request> {
+    let bad = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_string());
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(bad("请求行无效"));
+    };
+    let (method, target) = (method.to_string(), target.to_string());
+
+    let mut len = 0;
+    let mut head = line.len();
+    loop {
+        line.clear();
+        head += reader.read_line(&mut line)?;
+        if head > MAX_HEAD {
+            return Err(bad("请求头过长"));
+        }
+        let ln = line.trim_end();
+        if ln.is_empty() {
+            break;
+        }
+        if let Some((k, v)) = ln.split_once(':') {
+            if k.trim().eq_ignore_ascii_case("content-length") {
+                len = v.trim().parse().map_err(|_| bad("Content-Length无效"))?;
+            }
+        }
+    }
+    if len > MAX_BODY {
+        return Err(bad("请求体过长"));
+    }
+    let mut body = vec![0; len];
+    reader.read_exact(&mut body)?;
+
+    let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+    Ok(Request {
+        method,
+        path: path.to_string(),
+        query: query.to_string(),
+        body: String::from_utf8(body).map_err(|_| bad("请求体不是UTF-8"))?,
+    })
+}
+
+pub fn write_response(mut stream: &TcpStream, res: &Response) -> io::Result<()> {
+    let reason = match res.status {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        503 => "Service Unavailable",
+        _ => "",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {reason}\r\n\
+         Content-Type: application/json; charset=utf-8\r\n\
+         Content-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\n\
+         Access-Control-Allow-Methods: GET, POST, DELETE, OPTIONS\r\n\
+         Access-Control-Allow-Headers: Content-Type\r\n\
+         Connection: close\r\n\r\n{}",
+        res.status,
+        res.body.len(),
+        res.body
+    )?;
+    stream.flush()
+}
+
+/// # 解析参数
+/// 合并查询参数与请求体中的扁平JSON对象，值须为非负整数
+/// - 如 `?x=1&y=2` 或 `{"x": 1, "y": 2}`
+pub fn params(req: &Request) -> Result<HashMap<String, u64>, String> {
+    let mut res = HashMap::new();
+    let mut put = |k: &str, v: &str| -> Result<(), String> {
+        let k = k.trim().trim_matches('"');
+        let v = v.trim();
+        let n = v
+            .parse()
+            .map_err(|_| format!("参数{k}应为非负整数，实际为{v}"))?;
+        res.insert(k.to_string(), n);
+        Ok(())
+    };
+    for kv in req.query.split('&').filter(|s| !s.is_empty()) {
+        let (k, v) = kv.split_once('=').unwrap_or((kv, ""));
+        put(k, v)?;
+    }
+    let body = req.body.trim();
+    if !body.is_empty() {
+        let inner = body
+            .strip_prefix('{')
+            .and_then(|b| b.strip_suffix('}'))
+            .ok_or("请求体应为JSON对象")?;
+        for kv in inner.split(',').filter(|s| !s.trim().is_empty()) {
+            let (k, v) = kv.split_once(':').ok_or("请求体应为JSON对象")?;
+            if v.trim() != "null" {
+                put(k, v)?;
+            }
+        }
+    }
+    Ok(res)
+}
+
+/// JSON字符串字面量
+pub fn json_str(s: &str) -> String {
+    let mut buf = String::with_capacity(s.len() + 2);
+    buf.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => buf.push_str("\\\""),
+            '\\' => buf.push_str("\\\\"),
+            '\n' => buf.push_str("\\n"),
+            c if (c as u32) < 0x20 => buf.push_str(&format!("\\u{:04x}", c as u32)),
+            c => buf.push(c),
+        }
+    }
+    buf.push('"');
+    buf
+}
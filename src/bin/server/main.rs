@@ -0,0 +1,67 @@
+//! # 本地HTTP/JSON对局服务
+//! 仅监听`127.0.0.1`，每个连接处理一个请求。
+//!
+//! | 方法 | 路径 | 参数 | 说明 |
+//! | --- | --- | --- | --- |
+//! | POST | `/games` | `width`, `height`, `mines`, `seed`? | 创建对局 |
+//! | GET | `/games/{id}` | | 玩家视角 |
+//! | POST | `/games/{id}/reveal` | `x`, `y` | 打开 |
+//! | POST | `/games/{id}/flag` | `x`, `y` | 插旗/取消 |
+//! | POST | `/games/{id}/chord` | `x`, `y` | 双击 |
+//! | GET | `/games/{id}/stats` | | 用时、操作数、进度 |
+//! | DELETE | `/games/{id}` | | 删除对局 |
+//!
+//! 参数可放在查询字符串中，也可以扁平JSON对象作为请求体。
+
+mod api;
+mod http;
+
+use std::{
+    io::Write,
+    net::{Ipv4Addr, TcpListener, TcpStream},
+    sync::Arc,
+    thread,
+    time::Duration,
+};
+
+use api::Sessions;
+
+const USAGE: &str = "args: [port]，默认8080，0表示由系统分配";
+
+fn serve(sessions: &Sessions, stream: TcpStream) {
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+    let res = match http::read_request(&stream) {
+        Ok(req) => api::handle(sessions, &req),
+        Err(e) => http::Response::error(400, &e.to_string()),
+    };
+    let _ = http::write_response(&stream, &res);
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let port = match args.first().map(|a| a.parse::<u16>()) {
+        None => 8080,
+        Some(Ok(p)) => p,
+        Some(Err(_)) => {
+            eprintln!("{USAGE}");
+            std::process::exit(2);
+        }
+    };
+    let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, port)) {
+        Ok(l) => l,
+        Err(e) => {
+            eprintln!("无法监听端口{port}：{e}");
+            std::process::exit(1);
+        }
+    };
+    if let Ok(addr) = listener.local_addr() {
+        // 首行输出实际地址，便于测试读取系统分配的端口
+        println!("listening on http://{addr}");
+        let _ = std::io::stdout().flush();
+    }
+    let sessions = Arc::new(Sessions::default());
+    for stream in listener.incoming().flatten() {
+        let sessions = Arc::clone(&sessions);
+        thread::spawn(move || serve(&sessions, stream));
+    }
+}
@@ -0,0 +1,89 @@
+use std::fmt::Write;
+
+use mines::{
+    game::{Game, Status},
+    location::Loc,
+};
+
+const RESET: &str = "\x1b[0m";
+/// 数字1~8的前景色（256色）
+const COLORS: [u8; 8] = [21, 28, 196, 18, 88, 30, 16, 244];
+
+const TIP: &str = " arrows/wasd move  space reveal  f flag  c chord  n new  q quit";
+
+/// 绘制整个画面
+/// - `pressed` 鼠标按下时预览的单元格
+pub fn frame(game: &Game, cursor: Loc, pressed: &[Loc]) -> String {
+    let map = game.map();
+    let (w, h) = (map.width as usize, map.height as usize);
+    let mut buf = String::with_capacity(w * h * 12);
+    // 回到左上角逐行覆盖，避免清屏闪烁
+    buf.push_str("\x1b[H");
+    let left = map.count as i64 - map.count_flagged() as i64;
+    let secs = game.elapsed().as_secs();
+    let _ = write!(buf, " mines {left:<4}  time {secs:>4}s\x1b[K\r\n\x1b[K\r\n");
+    for y in 0..h {
+        for x in 0..w {
+            let loc = Loc::from(x, y);
+            let here = loc == cursor && !game.is_over();
+            buf.push(' ');
+            cell(&mut buf, game, loc, here, pressed.contains(&loc));
+        }
+        buf.push_str("\x1b[K\r\n");
+    }
+    buf.push_str("\x1b[K\r\n");
+    match game.status() {
+        Status::Won => {
+            let _ = write!(
+                buf,
+                " \x1b[1;32mYOU WIN\x1b[0m  time {:.1}s  3BV {}  clicks {}\r\n n new  q quit",
+                game.elapsed().as_secs_f64(),
+                map.count_3bv(),
+                game.actions()
+            );
+        }
+        Status::Lost => {
+            let _ = write!(
+                buf,
+                " \x1b[1;31mBOOM\x1b[0m  progress {:.0}%\r\n n new  q quit",
+                game.progress() * 100.0
+            );
+        }
+        _ => buf.push_str(TIP),
+    }
+    buf.push_str("\x1b[J");
+    buf
+}
+
+/// 绘制单个单元格，占一个字符宽度
+fn cell(buf: &mut String, game: &Game, loc: Loc, here: bool, pressed: bool) {
+    let Some(c) = game.map().get_by_loc(loc) else {
+        return;
+    };
+    let over = game.status() == Status::Lost;
+    if here {
+        buf.push_str("\x1b[7m");
+    }
+    if c.is_reveal() && c.is_mine() {
+        if game.boom() == Some(loc) {
+            buf.push_str("\x1b[41m");
+        }
+        buf.push('*');
+    } else if c.is_flagged() {
+        if over && !c.is_mine() {
+            // 插错的旗
+            buf.push_str("\x1b[31mX");
+        } else {
+            buf.push_str("\x1b[1;31mF");
+        }
+    } else if !c.is_reveal() {
+        // 按下未松开时显示为凹陷的空白
+        buf.push(if pressed { ' ' } else { '·' });
+    } else if c.is_empty() {
+        buf.push(' ');
+    } else {
+        let v = c.get_warn();
+        let _ = write!(buf, "\x1b[1;38;5;{}m{v}", COLORS[v as usize - 1]);
+    }
+    buf.push_str(RESET);
+}
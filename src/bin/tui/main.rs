@@ -0,0 +1,187 @@
+mod draw;
+mod mouse;
+mod term;
+
+use std::io::{stdout, Write};
+
+use mines::{
+    game::{Action, Game},
+    location::Loc,
+    mmap::MineMap,
+};
+use mouse::{Button, Kind, Mouse};
+use term::{Input, Key, RawMode};
+
+const USAGE: &str = "args: [width height count]";
+
+/// 鼠标按下后松开时执行的操作
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Press {
+    Reveal,
+    Chord,
+}
+
+struct App {
+    game: Game,
+    cursor: Loc,
+    quit: bool,
+    /// 左、右键是否按住
+    held: (bool, bool),
+    press: Option<Press>,
+    /// 按住时鼠标所在的单元格
+    hover: Option<Loc>,
+}
+
+impl App {
+    fn new(count: u16, width: u8, height: u8) -> Result<Self, String> {
+        Ok(Self {
+            game: Game::new(MineMap::new(count, width, height)?),
+            cursor: Loc(width / 2, height / 2),
+            quit: false,
+            held: (false, false),
+            press: None,
+            hover: None,
+        })
+    }
+
+    fn restart(&mut self) {
+        let m = self.game.map();
+        // 参数已在首次构造时验证过
+        if let Ok(map) = MineMap::new(m.count, m.width, m.height) {
+            self.game = Game::new(map);
+        }
+    }
+
+    fn handle(&mut self, key: Key) {
+        let (w, h) = (self.game.map().width, self.game.map().height);
+        let Loc(x, y) = self.cursor;
+        match key {
+            Key::Up | Key::Char('w') | Key::Char('k') => self.cursor.1 = y.saturating_sub(1),
+            Key::Down | Key::Char('s') | Key::Char('j') => self.cursor.1 = (y + 1).min(h - 1),
+            Key::Left | Key::Char('a') | Key::Char('h') => self.cursor.0 = x.saturating_sub(1),
+            Key::Right | Key::Char('d') | Key::Char('l') => self.cursor.0 = (x + 1).min(w - 1),
+            Key::Char(' ') => {
+                self.game.act(Action::Reveal(self.cursor));
+            }
+            Key::Char('f') => {
+                self.game.act(Action::Flag(self.cursor));
+            }
+            Key::Char('c') | Key::Enter => {
+                self.game.act(Action::Chord(self.cursor));
+            }
+            Key::Char('n') => self.restart(),
+            Key::Char('q') | Key::Esc => self.quit = true,
+            Key::Mouse(m) => self.click(m),
+            _ => {}
+        }
+    }
+
+    /// 左键松开时打开，右键按下时插旗，中键或左右键同时按下松开时双击
+    fn click(&mut self, m: Mouse) {
+        let (w, h) = (self.game.map().width, self.game.map().height);
+        let loc = m.loc(w, h);
+        if let Some(loc) = loc {
+            self.cursor = loc;
+        }
+        match (m.kind, m.button) {
+            (Kind::Press, Button::Left) => {
+                self.held.0 = true;
+                self.press = Some(if self.held.1 {
+                    Press::Chord
+                } else {
+                    Press::Reveal
+                });
+            }
+            (Kind::Press, Button::Right) => {
+                self.held.1 = true;
+                if self.held.0 {
+                    self.press = Some(Press::Chord);
+                } else if let Some(loc) = loc {
+                    self.game.act(Action::Flag(loc));
+                }
+            }
+            (Kind::Press, Button::Middle) => self.press = Some(Press::Chord),
+            (Kind::Drag, _) => {}
+            (Kind::Release, b) => {
+                match b {
+                    Button::Left => self.held.0 = false,
+                    Button::Right => self.held.1 = false,
+                    Button::Middle => {}
+                }
+                // 左右键同时按下时，先松开的键触发操作
+                if let (Some(p), Some(loc)) = (self.press.take(), loc) {
+                    self.game.act(match p {
+                        Press::Reveal => Action::Reveal(loc),
+                        Press::Chord => Action::Chord(loc),
+                    });
+                }
+                self.hover = None;
+                return;
+            }
+        }
+        self.hover = loc;
+    }
+
+    /// 鼠标按住时需要显示为按下的单元格
+    fn pressed(&self) -> Vec<Loc> {
+        let (Some(p), Some(loc)) = (self.press, self.hover) else {
+            return Vec::new();
+        };
+        let mut ls = vec![loc];
+        if p == Press::Chord {
+            ls.extend(loc.get_around());
+        }
+        ls
+    }
+}
+
+fn parse_args() -> Result<(u16, u8, u8), String> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match &args[..] {
+        [] => Ok((10, 9, 9)),
+        [w, h, c] => {
+            let bad = |a: &String| format!("无效参数：{a}");
+            Ok((
+                c.parse().map_err(|_| bad(c))?,
+                w.parse().map_err(|_| bad(w))?,
+                h.parse().map_err(|_| bad(h))?,
+            ))
+        }
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+fn run(mut app: App) -> std::io::Result<()> {
+    let _raw = RawMode::enter()?;
+    let mut out = stdout();
+    let mut last = String::new();
+    let mut input = Input::default();
+    while !app.quit {
+        // 画面不变时不重绘，计时器每秒变化一次
+        let frame = draw::frame(&app.game, app.cursor, &app.pressed());
+        if frame != last {
+            out.write_all(frame.as_bytes())?;
+            out.flush()?;
+            last = frame;
+        }
+        for key in input.read()? {
+            app.handle(key);
+        }
+    }
+    Ok(())
+}
+
+fn main() {
+    let app = parse_args().and_then(|(c, w, h)| App::new(c, w, h));
+    let app = match app {
+        Ok(app) => app,
+        Err(e) => {
+            eprintln!("{e}\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+    if let Err(e) = run(app) {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
+}
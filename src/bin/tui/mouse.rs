@@ -0,0 +1,145 @@
+use mines::location::Loc;
+
+/// 开启鼠标上报：按键、拖动、SGR(1006)格式
+pub const ENABLE: &str = "\x1b[?1000h\x1b[?1002h\x1b[?1006h";
+pub const DISABLE: &str = "\x1b[?1006l\x1b[?1002l\x1b[?1000l";
+
+/// 棋盘左上角所在的终端行（从1开始）
+pub const TOP: u16 = 3;
+/// 棋盘左上角所在的终端列（从1开始）
+pub const LEFT: u16 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Button {
+    Left,
+    Middle,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Press,
+    Release,
+    /// 按住拖动
+    Drag,
+}
+
+/// 鼠标事件，坐标为终端的列、行（从1开始）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mouse {
+    pub button: Button,
+    pub kind: Kind,
+    pub col: u16,
+    pub row: u16,
+}
+
+impl Mouse {
+    /// 换算为棋盘坐标
+    /// - 每个单元格占两列：一个空格加一个字符，两列都算作该单元格
+    pub fn loc(&self, width: u8, height: u8) -> Option<Loc> {
+        let x = self.col.checked_sub(LEFT)? / 2;
+        let y = self.row.checked_sub(TOP)?;
+        if x < width as u16 && y < height as u16 {
+            Some(Loc(x as u8, y as u8))
+        } else {
+            None
+        }
+    }
+}
+
+/// 解析 `ESC [ < b ; col ; row (M|m)` 格式的事件
+/// # Returns
+/// - `(事件, 消耗的字节数)`；不支持的事件（如滚轮）为`None`
+/// - 序列不完整时返回`None`
+pub fn parse(bs: &[u8]) -> Option<(Option<Mouse>, usize)> {
+    let body = bs.strip_prefix(b"\x1b[<")?;
+    let end = body.iter().position(|&b| b == b'M' || b == b'm')?;
+    let used = end + 4;
+    let Ok(text) = std::str::from_utf8(&body[..end]) else {
+        return Some((None, used));
+    };
+    let mut ns = text.split(';').map(|v| v.parse::<u16>().ok());
+    let (Some(Some(b)), Some(Some(col)), Some(Some(row))) = (ns.next(), ns.next(), ns.next())
+    else {
+        return Some((None, used));
+    };
+    // 低两位为按键，+32 表示移动，64 以上为滚轮
+    if b >= 64 {
+        return Some((None, used));
+    }
+    let button = match b & 3 {
+        0 => Button::Left,
+        1 => Button::Middle,
+        2 => Button::Right,
+        _ => return Some((None, used)),
+    };
+    let kind = if body[end] == b'm' {
+        Kind::Release
+    } else if b & 32 != 0 {
+        Kind::Drag
+    } else {
+        Kind::Press
+    };
+    Some((
+        Some(Mouse {
+            button,
+            kind,
+            col,
+            row,
+        }),
+        used,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(col: u16, row: u16) -> Mouse {
+        Mouse {
+            button: Button::Left,
+            kind: Kind::Press,
+            col,
+            row,
+        }
+    }
+
+    #[test]
+    fn parse_sgr() {
+        let (m, used) = parse(b"\x1b[<0;5;4Mrest").unwrap();
+        assert_eq!(used, 9);
+        assert_eq!(m, Some(at(5, 4)));
+        let (m, _) = parse(b"\x1b[<2;12;30m").unwrap();
+        let m = m.unwrap();
+        assert_eq!(
+            (m.button, m.kind, m.col, m.row),
+            (Button::Right, Kind::Release, 12, 30)
+        );
+        let (m, _) = parse(b"\x1b[<33;1;1M").unwrap();
+        assert_eq!(
+            m.map(|m| (m.button, m.kind)),
+            Some((Button::Middle, Kind::Drag))
+        );
+        // 滚轮与格式错误的序列被消耗但不产生事件
+        assert_eq!(parse(b"\x1b[<64;1;1M"), Some((None, 10)));
+        assert_eq!(parse(b"\x1b[<0;x;1M"), Some((None, 9)));
+        // 不完整
+        assert_eq!(parse(b"\x1b[<0;5;"), None);
+        assert_eq!(parse(b"abc"), None);
+    }
+
+    #[test]
+    fn loc_mapping() {
+        // 每个单元格占两列，棋盘从第LEFT列、第TOP行开始
+        assert_eq!(at(LEFT, TOP).loc(9, 9), Some(Loc(0, 0)));
+        assert_eq!(at(LEFT + 1, TOP).loc(9, 9), Some(Loc(0, 0)));
+        assert_eq!(at(LEFT + 2, TOP + 1).loc(9, 9), Some(Loc(1, 1)));
+        assert_eq!(at(LEFT + 17, TOP + 8).loc(9, 9), Some(Loc(8, 8)));
+        // 棋盘以外
+        assert_eq!(at(LEFT + 18, TOP).loc(9, 9), None);
+        assert_eq!(at(LEFT, TOP + 9).loc(9, 9), None);
+        assert_eq!(at(LEFT, TOP - 1).loc(9, 9), None);
+        assert_eq!(at(LEFT - 1, TOP).loc(9, 9), None);
+        assert_eq!(at(0, 0).loc(9, 9), None);
+    }
+}
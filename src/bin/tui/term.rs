@@ -0,0 +1,190 @@
+use std::{
+    io::{self, Read, Write},
+    process::{Command, Stdio},
+};
+
+use crate::mouse::{self, Mouse};
+
+/// 进入备用屏幕、隐藏光标
+const ENTER: &str = "\x1b[?1049h\x1b[?25l";
+/// 显示光标、退出备用屏幕
+const LEAVE: &str = "\x1b[?25h\x1b[?1049l";
+
+/// # 终端原始模式
+/// 构造时通过`stty`关闭回显与行缓冲，析构时恢复原设置
+pub struct RawMode {
+    saved: String,
+}
+
+fn stty(args: &[&str]) -> io::Result<String> {
+    let out = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()?;
+    if !out.status.success() {
+        return Err(io::Error::other("stty 执行失败，请在终端中运行！"));
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+impl RawMode {
+    pub fn enter() -> io::Result<Self> {
+        let saved = stty(&["-g"])?;
+        // 读取最多等待0.1秒，以便刷新计时器
+        stty(&["raw", "-echo", "min", "0", "time", "1"])?;
+        let mut out = io::stdout();
+        out.write_all(ENTER.as_bytes())?;
+        out.write_all(mouse::ENABLE.as_bytes())?;
+        out.flush()?;
+        Ok(Self { saved })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let mut out = io::stdout();
+        let _ = out.write_all(mouse::DISABLE.as_bytes());
+        let _ = out.write_all(LEAVE.as_bytes());
+        let _ = out.flush();
+        let _ = stty(&[&self.saved]);
+    }
+}
+
+/// 按键
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Char(char),
+    Enter,
+    Esc,
+    Mouse(Mouse),
+}
+
+/// 不完整序列的长度上限，超过后视为无效输入丢弃
+const MAX_PENDING: usize = 64;
+
+/// # 输入缓冲
+/// 一次读取可能在转义序列中间截断，未解析完的部分留到下次读取时补全
+#[derive(Default)]
+pub struct Input {
+    pending: Vec<u8>,
+}
+
+impl Input {
+    /// 读取一批输入，超时无输入时返回空集
+    pub fn read(&mut self) -> io::Result<Vec<Key>> {
+        let mut buf = [0u8; 256];
+        let n = io::stdin().read(&mut buf)?;
+        Ok(self.feed(&buf[..n]))
+    }
+
+    /// 追加输入并解析
+    pub fn feed(&mut self, bs: &[u8]) -> Vec<Key> {
+        self.pending.extend_from_slice(bs);
+        let (keys, used) = parse_keys(&self.pending);
+        self.pending.drain(..used);
+        if self.pending.len() > MAX_PENDING {
+            self.pending.clear();
+        }
+        keys
+    }
+}
+
+/// 解析按键序列
+/// # Returns
+/// - `(按键, 消耗的字节数)`，末尾不完整的转义序列不消耗
+pub fn parse_keys(bs: &[u8]) -> (Vec<Key>, usize) {
+    let mut keys = Vec::new();
+    let mut at = 0;
+    while let Some(&b) = bs.get(at) {
+        let rest = &bs[at..];
+        let (key, used) = match b {
+            // 单独的ESC视为按键，其后紧跟`[`或`O`时等待序列补全
+            0x1b if rest.len() == 1 => (Some(Key::Esc), 1),
+            0x1b if rest.len() == 2 && matches!(rest[1], b'[' | b'O') => break,
+            0x1b => match rest.get(1..3) {
+                Some(b"[A" | b"OA") => (Some(Key::Up), 3),
+                Some(b"[B" | b"OB") => (Some(Key::Down), 3),
+                Some(b"[C" | b"OC") => (Some(Key::Right), 3),
+                Some(b"[D" | b"OD") => (Some(Key::Left), 3),
+                Some(b"[<") => match mouse::parse(rest) {
+                    Some((m, used)) => (m.map(Key::Mouse), used),
+                    None => break,
+                },
+                // 其余转义序列整段忽略
+                Some([b'[', ..]) => match csi_len(rest) {
+                    Some(n) => (None, n),
+                    None => break,
+                },
+                _ => (Some(Key::Esc), 1),
+            },
+            b'\r' | b'\n' => (Some(Key::Enter), 1),
+            // Ctrl-C 在原始模式下不会产生信号
+            0x03 => (Some(Key::Char('q')), 1),
+            0x20..=0x7e => (Some(Key::Char(b as char)), 1),
+            _ => (None, 1),
+        };
+        keys.extend(key);
+        at += used;
+    }
+    (keys, at)
+}
+
+/// CSI序列的长度：`ESC [` 之后直到终止字节（0x40~0x7e），不完整时为`None`
+fn csi_len(bs: &[u8]) -> Option<usize> {
+    bs.iter()
+        .skip(2)
+        .position(|b| (0x40..=0x7e).contains(b))
+        .map(|p| p + 3)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys() {
+        let (keys, used) = parse_keys(b"\x1b[Ax\r\x1bOD\x1b[2~ \x03");
+        assert_eq!(
+            keys,
+            [
+                Key::Up,
+                Key::Char('x'),
+                Key::Enter,
+                Key::Left,
+                Key::Char(' '),
+                Key::Char('q')
+            ]
+        );
+        assert_eq!(used, 14);
+        assert_eq!(parse_keys(b"\x1b"), (vec![Key::Esc], 1));
+        assert_eq!(parse_keys(b"\x1bq"), (vec![Key::Esc, Key::Char('q')], 2));
+    }
+
+    #[test]
+    fn partial_sequence_kept() {
+        let mut input = Input::default();
+        // 鼠标序列被截断
+        assert_eq!(input.feed(b"f\x1b[<0;3;"), [Key::Char('f')]);
+        let keys = input.feed(b"4Mn");
+        assert_eq!(keys.len(), 2);
+        let Key::Mouse(m) = keys[0] else {
+            panic!("{keys:?}")
+        };
+        assert_eq!((m.col, m.row), (3, 4));
+        assert_eq!(keys[1], Key::Char('n'));
+        // 截断在ESC [之后
+        assert!(input.feed(b"\x1b[").is_empty());
+        assert_eq!(input.feed(b"B"), [Key::Down]);
+        // 其他CSI序列
+        assert!(input.feed(b"\x1b[1;5").is_empty());
+        assert_eq!(input.feed(b"Hs"), [Key::Char('s')]);
+        // 无法补全的垃圾最终被丢弃
+        input.feed(b"\x1b[<");
+        input.feed(&[b'1'; 70]);
+        assert_eq!(input.feed(b"a"), [Key::Char('a')]);
+    }
+}
@@ -0,0 +1,124 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{cell::Cell, game::Action, location::Loc};
+
+/// 机器人的决策
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Decision {
+    pub action: Action,
+    /// 是否为无法推理时的猜测
+    pub guess: bool,
+}
+
+/// # 扫雷机器人
+/// 只能看到玩家视角的数据，见`MineMap::view`
+pub trait Bot: Sync {
+    fn name(&self) -> &str;
+
+    /// 根据当前局面给出下一步操作
+    /// # Argument
+    /// - `view` 玩家视角的数据，按行排列
+    fn decide(&self, width: u8, height: u8, view: &[u8]) -> Decision;
+}
+
+/// 按坐标读取玩家视角的单元格
+#[inline]
+fn at(view: &[u8], w: usize, Loc(x, y): Loc) -> Option<Cell> {
+    if (x as usize) < w {
+        view.get(y as usize * w + x as usize).map(|&v| Cell(v))
+    } else {
+        None
+    }
+}
+
+/// 周围一圈的有效坐标
+fn around(w: u8, h: u8, loc: Loc) -> impl Iterator<Item = Loc> {
+    loc.get_around()
+        .into_iter()
+        .filter(move |&Loc(x, y)| x < w && y < h)
+}
+
+/// 从视角数据生成局部随机数，保证同一局面下决策可复现
+fn rng_of(view: &[u8]) -> ChaCha8Rng {
+    let seed = view.iter().fold(0xcbf2_9ce4_8422_2325u64, |h, &v| {
+        (h ^ v as u64).wrapping_mul(0x0100_0000_01b3)
+    });
+    ChaCha8Rng::seed_from_u64(seed)
+}
+
+/// 随机打开未知单元格
+pub struct Blind;
+
+impl Bot for Blind {
+    fn name(&self) -> &str {
+        "blind"
+    }
+
+    fn decide(&self, width: u8, _height: u8, view: &[u8]) -> Decision {
+        let hidden: Vec<usize> = (0..view.len())
+            .filter(|&i| {
+                let c = Cell(view[i]);
+                !c.is_reveal() && !c.is_flagged()
+            })
+            .collect();
+        let w = width as usize;
+        let i = match hidden.len() {
+            0 => 0,
+            n => hidden[rng_of(view).gen_range(0..n)],
+        };
+        Decision {
+            action: Action::Reveal(Loc::from(i % w, i / w)),
+            guess: true,
+        }
+    }
+}
+
+/// # 单点推理
+/// - 数字等于周围未知数 ⇒ 全部插旗
+/// - 数字等于周围旗数 ⇒ 打开周围一圈
+/// - 都不满足时随机猜测
+pub struct Simple;
+
+impl Bot for Simple {
+    fn name(&self) -> &str {
+        "simple"
+    }
+
+    fn decide(&self, width: u8, height: u8, view: &[u8]) -> Decision {
+        let w = width as usize;
+        for i in 0..view.len() {
+            let c = Cell(view[i]);
+            if !c.is_reveal() || c.is_empty() || c.is_mine() {
+                continue;
+            }
+            let loc = Loc::from(i % w, i / w);
+            let (mut flags, mut hidden) = (0, None);
+            let mut unknown = 0;
+            for a in around(width, height, loc) {
+                let Some(n) = at(view, w, a) else { continue };
+                if n.is_flagged() {
+                    flags += 1;
+                } else if !n.is_reveal() {
+                    unknown += 1;
+                    hidden = Some(a);
+                }
+            }
+            let Some(hidden) = hidden else { continue };
+            let warn = c.get_warn();
+            if flags == warn {
+                return Decision {
+                    action: Action::Chord(loc),
+                    guess: false,
+                };
+            }
+            if flags + unknown == warn {
+                return Decision {
+                    action: Action::Flag(hidden),
+                    guess: false,
+                };
+            }
+        }
+        Blind.decide(width, height, view)
+    }
+}
@@ -12,9 +12,61 @@ const BIT_WARN: u8 = 0x1F;
 /// - `1000 0000` 是否已打开
 /// - `0100 0000` 是否已插旗
 /// - `0001 1111` 周围地雷数
+/// ### Serde
+/// 表示为解析后的字段，不暴露位结构：
+/// `{"revealed": bool, "flagged": bool, "mine": bool, "warn": u8}`，
+/// 其中`warn`为周围地雷数（0~8），地雷本身也记录其周围的地雷数。
 #[derive(Clone, Copy, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "CellRepr", into = "CellRepr")
+)]
 pub struct Cell(pub u8);
 
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct CellRepr {
+    revealed: bool,
+    flagged: bool,
+    mine: bool,
+    warn: u8,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<CellRepr> for Cell {
+    type Error = String;
+    fn try_from(r: CellRepr) -> std::result::Result<Self, String> {
+        if r.warn > 8 {
+            return Err(format!("周围地雷数无效：{}", r.warn));
+        }
+        let mut v = r.warn;
+        if r.mine {
+            v += 9;
+        }
+        if r.revealed {
+            v |= BIT_REVEAL;
+        }
+        if r.flagged {
+            v |= BIT_FLAG;
+        }
+        Ok(Cell(v))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Cell> for CellRepr {
+    fn from(c: Cell) -> Self {
+        let mine = c.is_mine();
+        CellRepr {
+            revealed: c.is_reveal(),
+            flagged: c.is_flagged(),
+            mine,
+            warn: if mine { c.get_warn() - 9 } else { c.get_warn() },
+        }
+    }
+}
+
 impl Cell {
     #[inline]
     pub fn get_warn(&self) -> u8 {
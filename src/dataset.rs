@@ -0,0 +1,148 @@
+use std::io::{self, Write};
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    cell::Cell,
+    game::{Action, Game},
+    location::Loc,
+    mmap::MineMap,
+    solver,
+};
+
+/// # 训练样本
+/// 某一时刻的玩家视角，以及对应的真实布局与精确概率
+pub struct Sample {
+    pub seed: u64,
+    pub count: u16,
+    pub width: u8,
+    pub height: u8,
+    /// 打开的步数
+    pub depth: u32,
+    /// 玩家视角，见`MineMap::view`
+    pub view: Vec<u8>,
+    /// 真实布局，地雷为1
+    pub mines: Vec<u8>,
+    /// 每个单元格为地雷的精确概率
+    pub probs: Vec<f32>,
+}
+
+/// 由数据集的基础种子与对局序号派生该局的种子（SplitMix64）
+pub fn derive_seed(base: u64, index: u64) -> u64 {
+    let mut z = base.wrapping_add(index.wrapping_add(1).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// 基于种子进行一局，打开随机步数后生成样本
+/// - 首次打开的位置与步数均由种子决定
+/// - 之后每步随机打开一个与已打开区域相邻的安全单元格
+/// # Returns
+/// - 对局提前结束或概率计算规模过大时返回`None`
+pub fn sample(seed: u64, count: u16, width: u8, height: u8, max_depth: u32) -> Option<Sample> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed ^ 0x5eed);
+    let mut game = Game::by_seed(MineMap::new(count, width, height).ok()?, seed);
+    let (w, h) = (width as usize, height as usize);
+    let first = Loc::from(rng.gen_range(0..w), rng.gen_range(0..h));
+    game.act(Action::Reveal(first));
+    let depth = rng.gen_range(0..=max_depth);
+    let layout = game.map().export(false);
+    let is_mine = |i: usize| Cell(layout[i + 2]).is_mine();
+    for _ in 0..depth {
+        if game.is_over() {
+            return None;
+        }
+        let view = game.view();
+        let next: Vec<usize> = (0..view.len())
+            .filter(|&i| !Cell(view[i]).is_reveal() && !is_mine(i))
+            .filter(|&i| {
+                Loc::from(i % w, i / w)
+                    .get_around()
+                    .iter()
+                    .any(|&a| game.map().get_by_loc(a).is_some_and(|c| c.is_reveal()))
+            })
+            .collect();
+        if next.is_empty() {
+            return None;
+        }
+        let i = next[rng.gen_range(0..next.len())];
+        game.act(Action::Reveal(Loc::from(i % w, i / w)));
+    }
+    if game.is_over() {
+        return None;
+    }
+    let view = game.view();
+    let probs = solver::probabilities(width, height, count, &view)?;
+    Some(Sample {
+        seed,
+        count,
+        width,
+        height,
+        depth,
+        mines: (0..view.len()).map(|i| is_mine(i) as u8).collect(),
+        probs: probs.into_iter().map(|p| p as f32).collect(),
+        view,
+    })
+}
+
+impl Sample {
+    /// # 写入二进制记录
+    /// 小端序，依次为：
+    /// - `seed: u64`, `count: u16`, `width: u8`, `height: u8`, `depth: u32`
+    /// - `view: [u8; size]` 玩家视角
+    /// - `mines: [u8; (size + 7) / 8]` 按位存放的真实布局，低位在前，末字节不足8位时高位补0
+    /// - `probs: [f32; size]` 精确概率
+    pub fn write_bin<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        out.write_all(&self.seed.to_le_bytes())?;
+        out.write_all(&self.count.to_le_bytes())?;
+        out.write_all(&[self.width, self.height])?;
+        out.write_all(&self.depth.to_le_bytes())?;
+        out.write_all(&self.view)?;
+        let mut bits = vec![0u8; self.mines.len().div_ceil(8)];
+        for (i, &m) in self.mines.iter().enumerate() {
+            bits[i / 8] |= m << (i % 8);
+        }
+        out.write_all(&bits)?;
+        for p in &self.probs {
+            out.write_all(&p.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    pub const CSV_HEADER: &'static str = "seed,count,width,height,depth,view,mines,probs";
+
+    /// # 写入一行CSV
+    /// - `view` 每个单元格一个字符：`?`未打开，`F`插旗，`0`~`8`数字
+    /// - `mines` 每个单元格一个字符：`1`地雷，`0`安全
+    /// - `probs` 以`;`分隔的概率
+    pub fn write_csv<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let view: String = self
+            .view
+            .iter()
+            .map(|&v| {
+                let c = Cell(v);
+                if c.is_reveal() {
+                    (b'0' + c.get_warn()) as char
+                } else if c.is_flagged() {
+                    'F'
+                } else {
+                    '?'
+                }
+            })
+            .collect();
+        let mines: String = self.mines.iter().map(|&m| (b'0' + m) as char).collect();
+        let probs: Vec<String> = self.probs.iter().map(|p| format!("{p:.6}")).collect();
+        writeln!(
+            out,
+            "{},{},{},{},{},{view},{mines},{}",
+            self.seed,
+            self.count,
+            self.width,
+            self.height,
+            self.depth,
+            probs.join(";")
+        )
+    }
+}
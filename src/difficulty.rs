@@ -0,0 +1,154 @@
+//! # 难度
+//! 标准难度与自定义难度，自定义难度须通过密度校验：
+//! 首次打开时安全区（默认为周围一圈，见`Safety`）必须能够全部留空。
+
+use std::{fmt, str::FromStr};
+
+use crate::mmap::{MineMap, Safety};
+
+/// 按由易到难的标准难度、自定义难度（宽、高、雷数）排序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Difficulty {
+    /// 初级 9x9/10
+    Beginner,
+    /// 中级 16x16/40
+    Intermediate,
+    /// 高级 30x16/99
+    Expert,
+    Custom {
+        width: u8,
+        height: u8,
+        count: u16,
+    },
+}
+
+impl Difficulty {
+    /// 标准难度，由易到难
+    pub const STANDARD: [Self; 3] = [Self::Beginner, Self::Intermediate, Self::Expert];
+
+    /// 自定义难度，与标准难度尺寸相同时返回标准难度
+    /// # Errors
+    /// - 见`validate`
+    pub fn custom(width: u8, height: u8, count: u16) -> Result<Self, String> {
+        validate(width, height, count)?;
+        Ok(Self::of(width, height, count))
+    }
+
+    /// 按尺寸匹配标准难度，不做校验
+    pub fn of(width: u8, height: u8, count: u16) -> Self {
+        Self::STANDARD
+            .into_iter()
+            .find(|d| d.size() == (width, height, count))
+            .unwrap_or(Self::Custom {
+                width,
+                height,
+                count,
+            })
+    }
+
+    /// `(宽, 高, 雷数)`
+    pub fn size(&self) -> (u8, u8, u16) {
+        match *self {
+            Self::Beginner => (9, 9, 10),
+            Self::Intermediate => (16, 16, 40),
+            Self::Expert => (30, 16, 99),
+            Self::Custom {
+                width,
+                height,
+                count,
+            } => (width, height, count),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Beginner => "beginner",
+            Self::Intermediate => "intermediate",
+            Self::Expert => "expert",
+            Self::Custom { .. } => "custom",
+        }
+    }
+
+    /// 地雷密度
+    pub fn density(&self) -> f64 {
+        let (w, h, c) = self.size();
+        c as f64 / (w as usize * h as usize) as f64
+    }
+
+    /// 按难度创建地图
+    pub fn map(&self) -> Result<MineMap, String> {
+        let (w, h, c) = self.size();
+        validate(w, h, c)?;
+        MineMap::new(c, w, h)
+    }
+}
+
+/// 默认保护策略下，首次打开时安全区的最大单元格数
+pub fn safe_area(width: u8, height: u8) -> usize {
+    Safety::default().max_area(width, height)
+}
+
+/// 按默认保护策略进行密度校验，见`validate_with`
+pub fn validate(width: u8, height: u8, count: u16) -> Result<(), String> {
+    validate_with(width, height, count, Safety::default())
+}
+
+/// # 密度校验
+/// # Errors
+/// - 区域小于2x2、没有地雷，或地雷多到无法留出安全区
+pub fn validate_with(width: u8, height: u8, count: u16, safety: Safety) -> Result<(), String> {
+    if width < 2 || height < 2 {
+        return Err("请设置更大的区域！".to_string());
+    }
+    if count < 1 {
+        return Err("请设置更多地雷！".to_string());
+    }
+    let (size, safe) = (
+        width as usize * height as usize,
+        safety.max_area(width, height).max(1),
+    );
+    if count as usize > size - safe {
+        return Err(format!(
+            "地雷过多：{width}x{height}留出{safe}格安全区后最多{}颗",
+            size - safe
+        ));
+    }
+    Ok(())
+}
+
+/// 标准难度为名称，自定义为`宽x高/雷数`
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Custom {
+                width,
+                height,
+                count,
+            } => write!(f, "{width}x{height}/{count}"),
+            d => f.write_str(d.name()),
+        }
+    }
+}
+
+/// 接受名称（可缩写为首字母）或`宽x高/雷数`，后者经过校验
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "b" | "beginner" => return Ok(Self::Beginner),
+            "i" | "intermediate" => return Ok(Self::Intermediate),
+            "e" | "expert" => return Ok(Self::Expert),
+            _ => {}
+        }
+        let (w, h, c) = parse_size(s).ok_or_else(|| format!("无法识别的难度：{s}"))?;
+        Self::custom(w, h, c)
+    }
+}
+
+/// 解析`宽x高/雷数`，不做校验
+pub(crate) fn parse_size(s: &str) -> Option<(u8, u8, u16)> {
+    let (wh, c) = s.trim().split_once('/')?;
+    let (w, h) = wh.split_once(['x', 'X'])?;
+    Some((w.parse().ok()?, h.parse().ok()?, c.parse().ok()?))
+}
@@ -0,0 +1,192 @@
+//! # 地雷分布
+//! 除均匀分布外，按权重逐颗抽取地雷：每颗地雷从剩余的候选单元格中
+//! 按权重随机选出，因此地雷数始终准确，安全区规则与`MineMap::new_game`相同。
+//! 权重全部为0时退化为均匀抽取。
+
+use rand::Rng;
+
+use crate::mmap::around_index;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Distribution {
+    /// 均匀分布
+    #[default]
+    Uniform,
+    /// 聚集：权重为`1 + strength * 周围已有地雷数`
+    Clustered { strength: f64 },
+    /// 分散：权重为`1 / (1 + strength * 周围已有地雷数)`
+    Dispersed { strength: f64 },
+    /// 密度渐变：权重从`from`线性变化到`to`，
+    /// 方向为从左到右，`vertical`时为从上到下
+    Gradient { from: f64, to: f64, vertical: bool },
+    /// 逐单元格的权重，按行排列，长度须与地图相同
+    Weighted(Vec<f64>),
+}
+
+impl Distribution {
+    /// 检查参数
+    /// # Errors
+    /// - 权重为负数或不是有限值，权重表长度与地图不符
+    pub fn validate(&self, width: u8, height: u8) -> Result<(), String> {
+        let ok = |v: f64| v.is_finite() && v >= 0.0;
+        match self {
+            Distribution::Uniform => Ok(()),
+            Distribution::Clustered { strength } | Distribution::Dispersed { strength } => {
+                if ok(*strength) {
+                    Ok(())
+                } else {
+                    Err(format!("强度须为非负数：{strength}"))
+                }
+            }
+            Distribution::Gradient { from, to, .. } => {
+                if ok(*from) && ok(*to) {
+                    Ok(())
+                } else {
+                    Err(format!("权重须为非负数：{from} -> {to}"))
+                }
+            }
+            Distribution::Weighted(ws) => {
+                let size = width as usize * height as usize;
+                if ws.len() != size {
+                    return Err(format!("权重表长度为{}，应为{size}", ws.len()));
+                }
+                match ws.iter().position(|&v| !ok(v)) {
+                    Some(i) => Err(format!("第{i}个权重须为非负数：{}", ws[i])),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+
+    /// 单元格`i`的权重
+    /// # Argument
+    /// - `near` 周围已布置的地雷数
+    fn weight(&self, i: usize, near: u8, w: usize, h: usize) -> f64 {
+        match self {
+            Distribution::Uniform => 1.0,
+            Distribution::Clustered { strength } => 1.0 + strength * near as f64,
+            Distribution::Dispersed { strength } => 1.0 / (1.0 + strength * near as f64),
+            Distribution::Gradient { from, to, vertical } => {
+                let (p, n) = if *vertical { (i / w, h) } else { (i % w, w) };
+                let t = if n > 1 {
+                    p as f64 / (n - 1) as f64
+                } else {
+                    0.0
+                };
+                from + (to - from) * t
+            }
+            Distribution::Weighted(ws) => ws.get(i).copied().unwrap_or(0.0),
+        }
+    }
+
+    /// 从候选单元格中抽取`count`颗地雷
+    /// - 用树状数组维护权重的前缀和，每颗地雷只更新周围一圈的权重，
+    ///   总耗时O(size + count * log(size))
+    pub(crate) fn pick<R: Rng>(
+        &self,
+        cells: &[usize],
+        count: usize,
+        w: usize,
+        h: usize,
+        rng: &mut R,
+    ) -> Vec<usize> {
+        // 单元格 -> 在`cells`中的位置
+        let mut slot = vec![usize::MAX; w * h];
+        for (k, &i) in cells.iter().enumerate() {
+            slot[i] = k;
+        }
+        let mut near = vec![0u8; w * h];
+        let mut weights: Vec<f64> = cells.iter().map(|&i| self.weight(i, 0, w, h)).collect();
+        let mut tree = Fenwick::new(&weights);
+        // 剩余的正权重单元格数，前缀和的浮点误差不影响它
+        let mut positive = weights.iter().filter(|&&v| v > 0.0).count();
+        // 剩余的候选位置，权重全部为0时从中均匀抽取
+        let mut pool: Vec<usize> = (0..cells.len()).collect();
+        let mut at: Vec<usize> = (0..cells.len()).collect();
+        let mut mines = Vec::with_capacity(count);
+        for _ in 0..count.min(cells.len()) {
+            let k = (positive > 0)
+                .then(|| {
+                    tree.find(rng.gen::<f64>() * tree.total())
+                        .min(cells.len() - 1)
+                })
+                // 浮点误差可能落在已抽取或权重为0的位置，此时同样均匀抽取
+                .filter(|&k| weights[k] > 0.0)
+                .unwrap_or_else(|| pool[rng.gen_range(0..pool.len())]);
+            if weights[k] > 0.0 {
+                positive -= 1;
+            }
+            tree.add(k, -weights[k]);
+            weights[k] = 0.0;
+            let last = *pool.last().unwrap_or(&k);
+            pool.swap_remove(at[k]);
+            at[last] = at[k];
+            slot[cells[k]] = usize::MAX;
+
+            let i = cells[k];
+            mines.push(i);
+            for a in around_index(i, w, h) {
+                near[a] += 1;
+                let j = slot[a];
+                if j != usize::MAX {
+                    let v = self.weight(a, near[a], w, h);
+                    positive = positive + (v > 0.0) as usize - (weights[j] > 0.0) as usize;
+                    tree.add(j, v - weights[j]);
+                    weights[j] = v;
+                }
+            }
+        }
+        mines
+    }
+}
+
+/// 树状数组，维护权重的前缀和
+struct Fenwick(Vec<f64>);
+
+impl Fenwick {
+    fn new(ws: &[f64]) -> Self {
+        let n = ws.len();
+        let mut t = vec![0.0; n + 1];
+        for i in 1..=n {
+            t[i] += ws[i - 1];
+            let p = i + (i & i.wrapping_neg());
+            if p <= n {
+                t[p] += t[i];
+            }
+        }
+        Self(t)
+    }
+
+    fn add(&mut self, k: usize, delta: f64) {
+        let mut i = k + 1;
+        while i < self.0.len() {
+            self.0[i] += delta;
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    fn total(&self) -> f64 {
+        let mut i = self.0.len() - 1;
+        let mut sum = 0.0;
+        while i > 0 {
+            sum += self.0[i];
+            i &= i - 1;
+        }
+        sum
+    }
+
+    /// 前缀和大于`r`的最小位置
+    fn find(&self, mut r: f64) -> usize {
+        let n = self.0.len() - 1;
+        let mut pos = 0;
+        let mut step = if n == 0 { 0 } else { 1 << n.ilog2() };
+        while step > 0 {
+            if pos + step <= n && self.0[pos + step] <= r {
+                pos += step;
+                r -= self.0[pos];
+            }
+            step >>= 1;
+        }
+        pos
+    }
+}
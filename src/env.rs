@@ -0,0 +1,194 @@
+use crate::{
+    cell::Cell,
+    game::{Action, Game, Status},
+    location::Loc,
+    mmap::MineMap,
+};
+
+/// 观测通道数：未打开、插旗、数字0~8
+pub const CHANNELS: usize = 11;
+const CH_HIDDEN: usize = 0;
+const CH_FLAG: usize = 1;
+const CH_WARN: usize = 2;
+
+/// 奖励设置
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rewards {
+    /// 每打开一个单元格
+    pub reveal: f32,
+    /// 获胜
+    pub win: f32,
+    /// 踩雷
+    pub lose: f32,
+    /// 无效操作：越界、重复打开、开局前插旗等
+    pub noop: f32,
+    /// 每一步的固定奖励，通常为负数以鼓励尽快结束
+    pub step: f32,
+}
+
+impl Default for Rewards {
+    fn default() -> Self {
+        Self {
+            reveal: 0.01,
+            win: 1.0,
+            lose: -1.0,
+            noop: -0.05,
+            step: 0.0,
+        }
+    }
+}
+
+/// 每一步的附加信息
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Info {
+    pub status: Status,
+    /// 本步打开的单元格数
+    pub revealed: usize,
+    /// 本步是否改变了局面
+    pub valid: bool,
+    pub progress: f64,
+}
+
+/// # 强化学习环境
+/// - 动作空间：`[0, size)` 打开对应单元格，`[size, 2 * size)` 插旗、拔旗
+/// - 观测：`[CHANNELS, height, width]` 的独热编码，按通道、行、列展开
+pub struct Env {
+    game: Game,
+    count: u16,
+    width: u8,
+    height: u8,
+    pub rewards: Rewards,
+}
+
+impl Env {
+    pub fn new(count: u16, width: u8, height: u8, rewards: Rewards) -> Result<Self, String> {
+        Ok(Self {
+            game: Game::new(MineMap::new(count, width, height)?),
+            count,
+            width,
+            height,
+            rewards,
+        })
+    }
+
+    #[inline]
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    #[inline]
+    pub fn action_space(&self) -> usize {
+        self.width as usize * self.height as usize * 2
+    }
+
+    #[inline]
+    pub fn observation_shape(&self) -> [usize; 3] {
+        [CHANNELS, self.height as usize, self.width as usize]
+    }
+
+    /// 动作编号转换为操作
+    pub fn decode_action(&self, action: usize) -> Option<Action> {
+        let (w, size) = (
+            self.width as usize,
+            self.width as usize * self.height as usize,
+        );
+        let i = action % size;
+        let loc = Loc::from(i % w, i / w);
+        match action / size {
+            0 => Some(Action::Reveal(loc)),
+            1 => Some(Action::Flag(loc)),
+            _ => None,
+        }
+    }
+
+    /// 开始新的一局，首次打开时基于种子布置地雷
+    pub fn reset(&mut self, seed: u64) -> Vec<f32> {
+        // 布局尚未生成，构造不会失败
+        let map = MineMap::new(self.count, self.width, self.height).unwrap();
+        self.game = Game::by_seed(map, seed);
+        self.observe()
+    }
+
+    /// 执行一步
+    /// - 每次分配新的观测，训练循环中可改用`step_into`复用缓冲区
+    /// # Returns
+    /// - `(观测, 奖励, 是否结束, 附加信息)`
+    pub fn step(&mut self, action: usize) -> (Vec<f32>, f32, bool, Info) {
+        let mut obs = vec![0.0; CHANNELS * self.width as usize * self.height as usize];
+        let (reward, done, info) = self.step_into(action, &mut obs);
+        (obs, reward, done, info)
+    }
+
+    /// 执行一步，观测写入`obs`，长度须为`CHANNELS * width * height`
+    /// # Returns
+    /// - `(奖励, 是否结束, 附加信息)`
+    pub fn step_into(&mut self, action: usize, obs: &mut [f32]) -> (f32, bool, Info) {
+        let before = (self.game.status(), self.game.actions());
+        let revealed = match self.decode_action(action) {
+            Some(a) => self.game.act(a),
+            None => 0,
+        };
+        let status = self.game.status();
+        let valid = self.game.actions() != before.1;
+        let r = &self.rewards;
+        let mut reward = r.step;
+        // 踩雷不算进展，不给打开奖励
+        if status != Status::Lost {
+            reward += r.reveal * revealed as f32;
+        }
+        if !valid {
+            reward += r.noop;
+        }
+        if before.0 != status {
+            match status {
+                Status::Won => reward += r.win,
+                Status::Lost => reward += r.lose,
+                _ => {}
+            }
+        }
+        let info = Info {
+            status,
+            revealed,
+            valid,
+            progress: self.game.progress(),
+        };
+        self.observe_into(obs);
+        (reward, self.game.is_over(), info)
+    }
+
+    /// 当前局面的观测
+    pub fn observe(&self) -> Vec<f32> {
+        let mut buf = vec![0.0; CHANNELS * self.width as usize * self.height as usize];
+        self.observe_into(&mut buf);
+        buf
+    }
+
+    /// 当前局面的观测写入`buf`，见`encode`
+    pub fn observe_into(&self, buf: &mut [f32]) {
+        encode(&self.game.view(), buf);
+    }
+}
+
+/// 将玩家视角的数据编码为独热观测
+/// - `buf`长度须为`CHANNELS * view.len()`，会被整体覆盖
+pub fn encode(view: &[u8], buf: &mut [f32]) {
+    let size = view.len();
+    assert_eq!(buf.len(), CHANNELS * size, "观测缓冲区长度不符！");
+    buf.fill(0.0);
+    for (i, &v) in view.iter().enumerate() {
+        let c = Cell(v);
+        let ch = if !c.is_reveal() {
+            if c.is_flagged() {
+                CH_FLAG
+            } else {
+                CH_HIDDEN
+            }
+        } else if c.is_mine() {
+            // 仅在踩雷后出现，视为未打开
+            CH_HIDDEN
+        } else {
+            CH_WARN + c.get_warn() as usize
+        };
+        buf[ch * size + i] = 1.0;
+    }
+}
@@ -0,0 +1,248 @@
+//! # 承诺-揭示
+//! 防止服务端在看到玩家的操作后移动地雷：
+//! 1. 开局前服务端公布`Commitment`，其中的摘要为
+//!    `SHA-256(DOMAIN, nonce, seed, width, height, count, start, safety, 布局)`
+//! 2. 对局中服务端对每步操作回应玩家视角（见`MineMap::view`）
+//! 3. 对局结束后公布`Opening`（种子与随机数）
+//! 4. 玩家用`verify`由种子重新生成布局，核对摘要与每一步的回应
+//!
+//! 布局在首次操作前就已确定，因此首次打开的安全区由公开的`start`与`safety`指定，
+//! 开局时自动打开。随机数`nonce`防止穷举种子反推布局。
+
+use crate::{
+    game::{Action, Game},
+    location::Loc,
+    mmap::{MineMap, Safety},
+};
+
+/// 摘要的域分隔前缀
+const DOMAIN: &[u8] = b"mines-commit-v1";
+
+/// 开局前公布的承诺
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Commitment {
+    pub width: u8,
+    pub height: u8,
+    pub count: u16,
+    /// 开局时打开的安全单元格
+    pub start: Option<Loc>,
+    /// `start`周围安全区的保护策略
+    pub safety: Safety,
+    pub digest: [u8; 32],
+}
+
+impl Commitment {
+    /// 摘要的十六进制表示
+    pub fn hex(&self) -> String {
+        self.digest.iter().map(|b| format!("{b:02x}")).collect()
+    }
+}
+
+/// 对局结束后公布的秘密
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Opening {
+    pub seed: u64,
+    pub nonce: [u8; 16],
+}
+
+/// 由种子生成布局，开局时打开的单元格已打开
+pub fn layout(
+    count: u16,
+    width: u8,
+    height: u8,
+    start: Option<Loc>,
+    safety: Safety,
+    seed: u64,
+) -> Result<MineMap, String> {
+    let mut map = MineMap::new(count, width, height)?;
+    map.new_game_with(start, safety, Some(seed));
+    if let Some(Loc(x, y)) = start {
+        map.reveal(x as usize, y as usize);
+    }
+    Ok(map)
+}
+
+/// 计算摘要
+fn digest(map: &MineMap, start: Option<Loc>, safety: Safety, o: &Opening) -> [u8; 32] {
+    let mut data = Vec::with_capacity(DOMAIN.len() + 32 + map.map.len());
+    data.extend(DOMAIN);
+    data.extend(o.nonce);
+    data.extend(o.seed.to_be_bytes());
+    data.extend([map.width, map.height]);
+    data.extend(map.count.to_be_bytes());
+    match start {
+        Some(Loc(x, y)) => data.extend([1, x, y]),
+        None => data.extend([0, 0, 0]),
+    }
+    data.extend(safety.encode());
+    data.extend(map.iter().map(|c| c.is_mine() as u8));
+    sha256(&data)
+}
+
+/// # 服务端
+/// 持有布局与秘密，结束前只对外给出承诺与玩家视角
+pub struct Dealer {
+    game: Game,
+    commitment: Commitment,
+    opening: Opening,
+}
+
+impl Dealer {
+    /// 以随机种子与随机数开局
+    pub fn new(
+        count: u16,
+        width: u8,
+        height: u8,
+        start: Option<Loc>,
+        safety: Safety,
+    ) -> Result<Self, String> {
+        let opening = Opening {
+            seed: rand::random(),
+            nonce: rand::random(),
+        };
+        Self::by_opening(count, width, height, start, safety, opening)
+    }
+
+    /// 以指定的种子与随机数开局
+    pub fn by_opening(
+        count: u16,
+        width: u8,
+        height: u8,
+        start: Option<Loc>,
+        safety: Safety,
+        opening: Opening,
+    ) -> Result<Self, String> {
+        if let Some(Loc(x, y)) = start {
+            if x >= width || y >= height {
+                return Err(format!("起始坐标越界：{}", Loc(x, y)));
+            }
+        }
+        let map = layout(count, width, height, start, safety, opening.seed)?;
+        let commitment = Commitment {
+            width,
+            height,
+            count,
+            start,
+            safety,
+            digest: digest(&map, start, safety, &opening),
+        };
+        Ok(Self {
+            game: Game::by_map(map).with_safety(safety),
+            commitment,
+            opening,
+        })
+    }
+
+    #[inline]
+    pub fn commitment(&self) -> &Commitment {
+        &self.commitment
+    }
+
+    #[inline]
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// 执行操作
+    /// # Returns
+    /// - 发给玩家的回应：操作后的玩家视角
+    pub fn act(&mut self, action: Action) -> Vec<u8> {
+        self.game.act(action);
+        self.game.view()
+    }
+
+    /// 结束后公布秘密，进行中返回`None`
+    pub fn opening(&self) -> Option<Opening> {
+        self.game.is_over().then_some(self.opening)
+    }
+}
+
+/// # 验证
+/// 由种子重新生成布局，核对承诺，并逐步重放操作核对服务端的回应
+/// # Argument
+/// - `transcript` 每步的操作与收到的回应
+pub fn verify(c: &Commitment, o: &Opening, transcript: &[(Action, Vec<u8>)]) -> Result<(), String> {
+    let map = layout(c.count, c.width, c.height, c.start, c.safety, o.seed)?;
+    if digest(&map, c.start, c.safety, o) != c.digest {
+        return Err("摘要不符：种子或布局与承诺不一致！".to_string());
+    }
+    let mut game = Game::by_map(map);
+    for (i, (action, res)) in transcript.iter().enumerate() {
+        game.act(*action);
+        if game.view() != *res {
+            return Err(format!("第{}步（{action:?}）的回应与布局不符！", i + 1));
+        }
+    }
+    Ok(())
+}
+
+/// SHA-256（FIPS 180-4）
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    const K: [u32; 64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4,
+        0xab1c5ed5, 0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe,
+        0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f,
+        0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7,
+        0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc,
+        0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b,
+        0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116,
+        0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7,
+        0xc67178f2,
+    ];
+    let mut h: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+    // 补位：0x80，若干0，64位长度
+    let mut msg = data.to_vec();
+    msg.push(0x80);
+    while msg.len() % 64 != 56 {
+        msg.push(0);
+    }
+    msg.extend((data.len() as u64 * 8).to_be_bytes());
+
+    let mut w = [0u32; 64];
+    for block in msg.chunks(64) {
+        for (i, b) in block.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([b[0], b[1], b[2], b[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = h;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = hh
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            hh = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (v, x) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+            *v = v.wrapping_add(x);
+        }
+    }
+    let mut out = [0; 32];
+    for (o, v) in out.chunks_mut(4).zip(h) {
+        o.copy_from_slice(&v.to_be_bytes());
+    }
+    out
+}
@@ -0,0 +1,333 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    cell::Cell,
+    location::Loc,
+    mmap::{MineMap, Safety},
+    replay::Event,
+};
+
+/// 玩家操作
+/// ### Serde
+/// 表示为`{"reveal": Loc}`、`{"flag": Loc}`、`{"chord": Loc}`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Action {
+    /// 打开单元格
+    Reveal(Loc),
+    /// 插旗、拔旗
+    Flag(Loc),
+    /// 周围标记数与数字相符时，打开周围一圈
+    Chord(Loc),
+}
+
+impl Action {
+    #[inline]
+    pub fn loc(&self) -> Loc {
+        match *self {
+            Action::Reveal(l) | Action::Flag(l) | Action::Chord(l) => l,
+        }
+    }
+}
+
+/// 对局状态
+/// ### Serde
+/// 表示为`"ready"`、`"playing"`、`"won"`、`"lost"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Status {
+    /// 尚未开局，首次打开时布置地雷
+    Ready,
+    Playing,
+    Won,
+    Lost,
+}
+
+/// # 对局
+/// 包装`MineMap`，负责首次点击开局、判定胜负、计时与计数
+/// ### Serde
+/// 表示为`{"map": MineMap, "seed": u64?, "safety": Safety, "status": Status,
+/// "boom": Loc?, "actions": u32, "elapsed_ms": u64, "history": [Event..]}`，
+/// 缺少`safety`时为默认策略，
+/// 反序列化后进行中的对局从当前时刻继续计时。
+pub struct Game {
+    pub(crate) map: MineMap,
+    pub(crate) seed: Option<u64>,
+    /// 首次打开的保护策略
+    pub(crate) safety: Safety,
+    pub(crate) status: Status,
+    /// 踩中的地雷
+    pub(crate) boom: Option<Loc>,
+    /// 有效操作数
+    pub(crate) actions: u32,
+    /// 已累计的用时
+    pub(crate) elapsed: Duration,
+    /// 本轮计时起点
+    pub(crate) since: Option<Instant>,
+    /// 已执行的有效操作
+    pub(crate) history: Vec<Event>,
+}
+
+impl Game {
+    /// 新对局，首次打开时随机布置地雷
+    pub fn new(map: MineMap) -> Self {
+        Self {
+            map,
+            seed: None,
+            safety: Safety::default(),
+            status: Status::Ready,
+            boom: None,
+            actions: 0,
+            elapsed: Duration::ZERO,
+            since: None,
+            history: Vec::new(),
+        }
+    }
+
+    /// 新对局，首次打开时基于种子布置地雷
+    pub fn by_seed(map: MineMap, seed: u64) -> Self {
+        let mut g = Self::new(map);
+        g.seed = Some(seed);
+        g
+    }
+
+    /// 设置首次打开的保护策略，开局后设置无效
+    pub fn with_safety(mut self, safety: Safety) -> Self {
+        self.safety = safety;
+        self
+    }
+
+    /// 基于已布置好地雷的地图继续对局
+    /// - 地图中的进度（打开、插旗）会保留
+    pub fn by_map(map: MineMap) -> Self {
+        let mut g = Self::new(map);
+        g.status = Status::Playing;
+        g.since = Some(Instant::now());
+        g.settle(None);
+        g
+    }
+
+    #[inline]
+    pub fn map(&self) -> &MineMap {
+        &self.map
+    }
+
+    #[inline]
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    #[inline]
+    pub fn safety(&self) -> Safety {
+        self.safety
+    }
+
+    #[inline]
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    #[inline]
+    pub fn is_over(&self) -> bool {
+        matches!(self.status, Status::Won | Status::Lost)
+    }
+
+    #[inline]
+    pub fn boom(&self) -> Option<Loc> {
+        self.boom
+    }
+
+    #[inline]
+    pub fn actions(&self) -> u32 {
+        self.actions
+    }
+
+    /// 已执行的有效操作，时间从开局算起
+    #[inline]
+    pub fn history(&self) -> &[Event] {
+        &self.history
+    }
+
+    /// 本局用时，结束后停止计时
+    pub fn elapsed(&self) -> Duration {
+        match self.since {
+            Some(t) => self.elapsed + t.elapsed(),
+            None => self.elapsed,
+        }
+    }
+
+    /// 进度：已打开的非雷单元格占比
+    pub fn progress(&self) -> f64 {
+        let (mut safe, mut open) = (0, 0);
+        for c in self.map.iter().filter(|c| !c.is_mine()) {
+            safe += 1;
+            if c.is_reveal() {
+                open += 1;
+            }
+        }
+        if safe == 0 {
+            return 0.0;
+        }
+        open as f64 / safe as f64
+    }
+
+    /// 玩家视角的数据，见`MineMap::view`
+    #[inline]
+    pub fn view(&self) -> Vec<u8> {
+        self.map.view()
+    }
+
+    /// 执行操作
+    /// # Returns
+    /// - 本次打开的单元格数
+    pub fn act(&mut self, action: Action) -> usize {
+        if self.is_over() {
+            return 0;
+        }
+        let loc = action.loc();
+        let Some(c) = self.map.get_by_loc(loc) else {
+            return 0;
+        };
+        let (x, y) = (loc.0 as usize, loc.1 as usize);
+        let count = match action {
+            Action::Reveal(_) => {
+                if self.status == Status::Ready {
+                    self.map.new_game_with(Some(loc), self.safety, self.seed);
+                    self.status = Status::Playing;
+                    self.since = Some(Instant::now());
+                } else if c.is_reveal() || c.is_flagged() {
+                    return 0;
+                }
+                self.map.reveal(x, y)
+            }
+            Action::Flag(_) => {
+                if self.status != Status::Playing || c.is_reveal() {
+                    return 0;
+                }
+                self.map.switch_flag(x, y);
+                0
+            }
+            Action::Chord(_) => {
+                if !c.is_reveal()
+                    || c.is_empty()
+                    || self.map.count_flagged_around(x, y) != c.get_warn() as usize
+                {
+                    return 0;
+                }
+                self.map.reveal_around(x, y)
+            }
+        };
+        self.actions += 1;
+        let ms = self.elapsed().as_millis().min(u32::MAX as u128) as u32;
+        self.history.push(Event { ms, action });
+        self.settle(Some(loc));
+        count
+    }
+
+    /// 检查胜负
+    fn settle(&mut self, at: Option<Loc>) {
+        let boom = match at {
+            Some(l) => std::iter::once(l)
+                .chain(l.get_around())
+                .find(|&a| self.map.get_by_loc(a).is_some_and(is_boom)),
+            None => self.find_boom(),
+        };
+        if boom.is_some() {
+            self.boom = boom;
+            self.status = Status::Lost;
+            self.map.reveal_all_mines();
+        } else if self.map.is_all_reveal() {
+            self.status = Status::Won;
+        } else {
+            return;
+        }
+        self.stop();
+    }
+
+    fn find_boom(&self) -> Option<Loc> {
+        let w = self.map.width as usize;
+        let i = self.map.iter().position(is_boom)?;
+        Some(Loc::from(i % w, i / w))
+    }
+
+    fn stop(&mut self) {
+        if let Some(t) = self.since.take() {
+            self.elapsed += t.elapsed();
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+struct GameRef<'a> {
+    map: &'a MineMap,
+    seed: Option<u64>,
+    safety: Safety,
+    status: Status,
+    boom: Option<Loc>,
+    actions: u32,
+    elapsed_ms: u64,
+    history: &'a [Event],
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct GameRepr {
+    map: MineMap,
+    seed: Option<u64>,
+    #[serde(default)]
+    safety: Safety,
+    status: Status,
+    boom: Option<Loc>,
+    actions: u32,
+    elapsed_ms: u64,
+    history: Vec<Event>,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Game {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        GameRef {
+            map: &self.map,
+            seed: self.seed,
+            safety: self.safety,
+            status: self.status,
+            boom: self.boom,
+            actions: self.actions,
+            elapsed_ms: self.elapsed().as_millis() as u64,
+            history: &self.history,
+        }
+        .serialize(s)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Game {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let r = GameRepr::deserialize(d)?;
+        Ok(Self {
+            map: r.map,
+            seed: r.seed,
+            safety: r.safety,
+            status: r.status,
+            boom: r.boom,
+            actions: r.actions,
+            elapsed: Duration::from_millis(r.elapsed_ms),
+            since: (r.status == Status::Playing).then(Instant::now),
+            history: r.history,
+        })
+    }
+}
+
+#[inline]
+fn is_boom(c: Cell) -> bool {
+    c.is_reveal() && c.is_mine()
+}
@@ -0,0 +1,251 @@
+//! # HTML导出
+//! 将结束的对局导出为单个HTML文件，不引用任何外部资源：
+//! - 最终局面（SVG）
+//! - 用时、3BV、点击数、效率
+//! - 由布局与操作序列驱动的逐步回放（内嵌JavaScript）
+
+use std::fmt::Write;
+
+use crate::{
+    game::{Action, Game, Status},
+    replay::Replay,
+    svg::{self, Options},
+};
+
+/// 由对局导出，用时取自对局计时
+pub fn export_game(game: &Game) -> String {
+    page(
+        &Replay::from_game(game),
+        game,
+        game.elapsed().as_millis() as u64,
+    )
+}
+
+/// 由录像导出，用时取最后一步操作的时间
+pub fn export(replay: &Replay) -> String {
+    let ms = replay.events.last().map_or(0, |e| e.ms as u64);
+    page(replay, &replay.play(), ms)
+}
+
+fn page(replay: &Replay, game: &Game, ms: u64) -> String {
+    let map = &replay.map;
+    let (w, h) = (map.width, map.height);
+    let bbbv = map.count_3bv();
+    let clicks = game.actions();
+    let secs = ms as f64 / 1000.0;
+    let result = match game.status() {
+        Status::Won => "WON",
+        Status::Lost => "LOST",
+        _ => "UNFINISHED",
+    };
+
+    let mut stats = String::new();
+    let _ = write!(
+        stats,
+        "<tr><th>Result</th><td>{result}</td></tr>\
+         <tr><th>Board</th><td>{w} x {h} / {}</td></tr>\
+         <tr><th>Time</th><td>{secs:.3} s</td></tr>\
+         <tr><th>3BV</th><td>{bbbv}</td></tr>\
+         <tr><th>Clicks</th><td>{clicks}</td></tr>",
+        map.count
+    );
+    if clicks > 0 {
+        let _ = write!(
+            stats,
+            "<tr><th>Efficiency</th><td>{:.0}%</td></tr>",
+            bbbv as f64 / clicks as f64 * 100.0
+        );
+    }
+    if ms > 0 {
+        let _ = write!(
+            stats,
+            "<tr><th>3BV/s</th><td>{:.3}</td></tr>",
+            bbbv as f64 / secs
+        );
+    }
+    if let Some(seed) = replay.seed {
+        let _ = write!(stats, "<tr><th>Seed</th><td>{seed}</td></tr>");
+    }
+
+    let mines: Vec<&str> = map
+        .iter()
+        .map(|c| if c.is_mine() { "1" } else { "0" })
+        .collect();
+    let mut events = String::new();
+    for (i, e) in replay.events.iter().enumerate() {
+        let kind = match e.action {
+            Action::Reveal(_) => 'r',
+            Action::Flag(_) => 'f',
+            Action::Chord(_) => 'c',
+        };
+        let loc = e.action.loc();
+        if i > 0 {
+            events.push(',');
+        }
+        let _ = write!(events, "[{},\"{kind}\",{},{}]", e.ms, loc.0, loc.1);
+    }
+
+    let board = svg::render_game(
+        game,
+        &Options {
+            labels: true,
+            ..Default::default()
+        },
+    );
+    TEMPLATE
+        .replace("@RESULT@", result)
+        .replace("@STATS@", &stats)
+        .replace("@BOARD@", board.trim_end())
+        .replace("@W@", &w.to_string())
+        .replace("@H@", &h.to_string())
+        .replace("@MINES@", &mines.join(","))
+        .replace("@EVENTS@", &events)
+}
+
+const TEMPLATE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Minesweeper - @RESULT@</title>
+<style>
+body { font-family: sans-serif; margin: 24px; background: #f4f4f4; }
+section { display: inline-block; vertical-align: top; margin: 0 32px 24px 0; }
+table.stats th { text-align: left; padding-right: 16px; }
+#grid { display: grid; gap: 0; border: 3px inset #808080; width: max-content; font: bold 16px monospace; }
+#grid div { width: 24px; height: 24px; line-height: 24px; text-align: center; box-sizing: border-box; background: #c0c0c0; }
+#grid .h { border: 3px outset #eee; line-height: 18px; }
+#grid .o { border: 1px solid #808080; line-height: 22px; }
+#grid .b { background: #f00; }
+#grid .x { color: #f00; }
+.n1 { color: #0000ff; } .n2 { color: #008000; } .n3 { color: #ff0000; } .n4 { color: #000080; }
+.n5 { color: #800000; } .n6 { color: #008080; } .n7 { color: #000000; } .n8 { color: #808080; }
+#controls { margin-top: 12px; }
+#controls input[type=range] { width: 240px; vertical-align: middle; }
+</style>
+</head>
+<body>
+<section>
+<h2>Final board</h2>
+@BOARD@
+</section>
+<section>
+<h2>Stats</h2>
+<table class="stats">@STATS@</table>
+</section>
+<section>
+<h2>Replay</h2>
+<div id="grid"></div>
+<div id="controls">
+<button id="first">|&lt;</button>
+<button id="prev">&lt;</button>
+<button id="play">play</button>
+<button id="next">&gt;</button>
+<button id="last">&gt;|</button>
+<input id="seek" type="range" min="0" value="0">
+<div id="info"></div>
+</div>
+</section>
+<script>
+const W = @W@, H = @H@;
+const MINES = [@MINES@];
+// [ms, r|f|c, x, y]
+const EVENTS = [@EVENTS@];
+
+const around = i => {
+  const x = i % W, y = (i / W) | 0, res = [];
+  for (let dy = -1; dy <= 1; dy++)
+    for (let dx = -1; dx <= 1; dx++) {
+      const ax = x + dx, ay = y + dy;
+      if ((dx || dy) && ax >= 0 && ay >= 0 && ax < W && ay < H) res.push(ay * W + ax);
+    }
+  return res;
+};
+const WARN = MINES.map((_, i) => around(i).reduce((s, a) => s + MINES[a], 0));
+
+// 0 hidden, 1 open, 2 flag
+function state(k) {
+  const s = new Array(W * H).fill(0);
+  let boom = -1;
+  const open = start => {
+    const stack = [start];
+    while (stack.length) {
+      const i = stack.pop();
+      if (s[i] !== 0) continue;
+      s[i] = 1;
+      if (MINES[i]) { if (boom < 0) boom = i; continue; }
+      if (WARN[i] === 0) stack.push(...around(i));
+    }
+  };
+  for (let j = 0; j < k; j++) {
+    const [, kind, x, y] = EVENTS[j], i = y * W + x;
+    if (kind === "r") open(i);
+    else if (kind === "f") { if (s[i] !== 1) s[i] = s[i] === 2 ? 0 : 2; }
+    else if (s[i] === 1 && WARN[i] > 0
+      && around(i).filter(a => s[a] === 2).length === WARN[i]) {
+      around(i).forEach(a => open(a));
+    }
+  }
+  return [s, boom];
+}
+
+const grid = document.getElementById("grid");
+const seek = document.getElementById("seek");
+const info = document.getElementById("info");
+grid.style.gridTemplateColumns = `repeat(${W}, 24px)`;
+const cells = MINES.map(() => grid.appendChild(document.createElement("div")));
+seek.max = EVENTS.length;
+let step = 0, timer = null;
+
+function show(k) {
+  step = Math.max(0, Math.min(EVENTS.length, k));
+  seek.value = step;
+  const [s, boom] = state(step);
+  cells.forEach((el, i) => {
+    let cls = "h", text = "";
+    if (s[i] === 2) {
+      text = "⚑";
+      if (boom >= 0 && !MINES[i]) cls = "h x";
+    } else if (s[i] === 1 || (boom >= 0 && MINES[i])) {
+      cls = "o";
+      if (MINES[i]) { text = "✹"; if (i === boom) cls = "o b"; }
+      else if (WARN[i]) { text = WARN[i]; cls = "o n" + WARN[i]; }
+    }
+    el.className = cls;
+    el.textContent = text;
+  });
+  const last = step ? EVENTS[step - 1] : null;
+  info.textContent = `step ${step}/${EVENTS.length}` + (last
+    ? `  ${(last[0] / 1000).toFixed(3)}s  ${{ r: "reveal", f: "flag", c: "chord" }[last[1]]} (${last[2]}, ${last[3]})`
+    : "");
+}
+
+function stop() {
+  clearTimeout(timer);
+  timer = null;
+  document.getElementById("play").textContent = "play";
+}
+
+// 按录像的时间间隔播放
+function tick() {
+  if (step >= EVENTS.length) return stop();
+  show(step + 1);
+  const wait = step < EVENTS.length ? EVENTS[step][0] - EVENTS[step - 1][0] : 0;
+  timer = setTimeout(tick, Math.min(Math.max(wait, 30), 2000));
+}
+
+document.getElementById("first").onclick = () => { stop(); show(0); };
+document.getElementById("prev").onclick = () => { stop(); show(step - 1); };
+document.getElementById("next").onclick = () => { stop(); show(step + 1); };
+document.getElementById("last").onclick = () => { stop(); show(EVENTS.length); };
+document.getElementById("play").onclick = () => {
+  if (timer) return stop();
+  if (step >= EVENTS.length) show(0);
+  document.getElementById("play").textContent = "pause";
+  tick();
+};
+seek.oninput = () => { stop(); show(+seek.value); };
+show(0);
+</script>
+</body>
+</html>
+"#;
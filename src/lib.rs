@@ -1,3 +1,24 @@
+pub mod ascii;
+pub mod bot;
 pub mod cell;
+pub mod dataset;
+pub mod difficulty;
+pub mod distribution;
+pub mod env;
+pub mod fair;
+pub mod game;
+pub mod html;
 pub mod location;
+pub mod mbf;
 pub mod mmap;
+pub mod quality;
+pub mod render;
+pub mod replay;
+pub mod save;
+pub mod share;
+pub mod shared;
+pub mod solver;
+pub mod stats;
+pub mod svg;
+pub mod tourney;
+pub mod wire;
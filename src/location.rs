@@ -1,7 +1,36 @@
 use smallvec::SmallVec;
 
+/// # 坐标
+/// ### Serde
+/// 表示为`{"x": u8, "y": u8}`
 #[derive(Debug, Clone, Copy, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "LocRepr", into = "LocRepr")
+)]
 pub struct Loc(pub u8, pub u8);
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct LocRepr {
+    x: u8,
+    y: u8,
+}
+
+#[cfg(feature = "serde")]
+impl From<LocRepr> for Loc {
+    fn from(LocRepr { x, y }: LocRepr) -> Self {
+        Loc(x, y)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Loc> for LocRepr {
+    fn from(Loc(x, y): Loc) -> Self {
+        LocRepr { x, y }
+    }
+}
 impl Loc {
     pub fn new(x: u8, y: u8) -> Self {
         Loc(x, y)
@@ -0,0 +1,72 @@
+//! # MBF（Minesweeper Board Format）
+//! 依次为：
+//! - `width: u8`, `height: u8`
+//! - `count: u16` 大端序
+//! - `mines: [(x: u8, y: u8); count]`
+//!
+//! 多个布局可首尾相接存放在同一文件中，见`import_all`。
+
+use crate::{location::Loc, mmap::MineMap};
+
+/// 导出为MBF
+/// # Errors
+/// - 尚未布置地雷
+pub fn export(map: &MineMap) -> Result<Vec<u8>, String> {
+    if map.iter().filter(|c| c.is_mine()).count() != map.count as usize {
+        return Err("尚未布置地雷，无法导出！".to_string());
+    }
+    let w = map.width as usize;
+    let mut res = Vec::with_capacity(4 + map.count as usize * 2);
+    res.extend([map.width, map.height]);
+    res.extend(map.count.to_be_bytes());
+    for (i, c) in map.iter().enumerate() {
+        if c.is_mine() {
+            res.extend([(i % w) as u8, (i / w) as u8]);
+        }
+    }
+    Ok(res)
+}
+
+/// 导入单个MBF布局
+/// - 数据长度必须与地雷数完全相符
+pub fn import(data: &[u8]) -> Result<MineMap, String> {
+    let (map, used) = read_one(data)?;
+    if used != data.len() {
+        return Err(format!("MBF数据多出{}字节！", data.len() - used));
+    }
+    Ok(map)
+}
+
+/// 导入首尾相接的多个MBF布局
+pub fn import_all(mut data: &[u8]) -> Result<Vec<MineMap>, String> {
+    let mut ls = Vec::new();
+    while !data.is_empty() {
+        let (map, used) = read_one(data).map_err(|e| format!("第{}个布局：{e}", ls.len() + 1))?;
+        ls.push(map);
+        data = &data[used..];
+    }
+    Ok(ls)
+}
+
+/// 读取一个布局
+/// # Returns
+/// - `(布局, 消耗的字节数)`
+fn read_one(data: &[u8]) -> Result<(MineMap, usize), String> {
+    let [w, h, c0, c1, rest @ ..] = data else {
+        return Err("MBF数据太短！[宽, 高, 地雷数(2字节)]".to_string());
+    };
+    let (w, h) = (*w, *h);
+    let count = u16::from_be_bytes([*c0, *c1]) as usize;
+    if count * 2 > rest.len() {
+        return Err(format!("地雷数为{count}，但只有{}个坐标！", rest.len() / 2));
+    }
+    let mut mines = Vec::with_capacity(count);
+    for p in rest[..count * 2].chunks(2) {
+        let (x, y) = (p[0], p[1]);
+        if x >= w || y >= h {
+            return Err(format!("地雷坐标越界：{}，区域{w}x{h}", Loc(x, y)));
+        }
+        mines.push(y as usize * w as usize + x as usize);
+    }
+    Ok((MineMap::by_mines(w, h, &mines)?, 4 + count * 2))
+}
@@ -1,7 +1,14 @@
 use std::collections::HashSet;
 
-use crate::{cell::Cell, location::Loc};
-use rand::{seq::SliceRandom, thread_rng, Rng};
+use crate::{
+    cell::Cell,
+    distribution::Distribution,
+    location::Loc,
+    quality::{self, Filter, Report},
+    render::{Plain, Renderer, Theme},
+};
+use rand::{seq::SliceRandom, thread_rng, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
 /// 表示无效下标。减1是为了后续增减操作不发生溢出。
 const M: usize = usize::MAX - 1;
@@ -82,6 +89,95 @@ fn get_around_index(i: usize, w: usize, h: usize) -> [usize; 8] {
     }
 }
 
+/// 周围一圈的有效下标，宽高须不小于2
+pub(crate) fn around_index(i: usize, w: usize, h: usize) -> impl Iterator<Item = usize> {
+    let size = w * h;
+    get_around_index(i, w, h)
+        .into_iter()
+        .filter(move |&a| a < size)
+}
+
+/// 首次打开的保护策略
+/// ### Serde
+/// 表示为`"none"`、`"cell"`、`"opening"`、`{"radius": u8}`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Safety {
+    /// 不保护，首次打开也可能踩雷
+    None,
+    /// 只保证点击的单元格无雷（Windows XP）
+    Cell,
+    /// 保证周围一圈无雷，首次打开必为空白
+    #[default]
+    Opening,
+    /// 保证切比雪夫距离不超过半径的单元格无雷，`Radius(1)`等同于`Opening`
+    Radius(u8),
+}
+
+impl Safety {
+    /// 安全区半径，`None`表示不保护
+    pub fn radius(&self) -> Option<u8> {
+        match *self {
+            Safety::None => None,
+            Safety::Cell => Some(0),
+            Safety::Opening => Some(1),
+            Safety::Radius(r) => Some(r),
+        }
+    }
+
+    /// 安全区的最大单元格数
+    pub fn max_area(&self, width: u8, height: u8) -> usize {
+        let Some(r) = self.radius() else { return 0 };
+        let d = 2 * r as usize + 1;
+        d.min(width as usize) * d.min(height as usize)
+    }
+
+    /// 存档、录像中的两字节表示：`[类型, 半径]`，
+    /// 类型0 不保护，1 单格，2 周围一圈，3 指定半径
+    pub(crate) fn encode(self) -> [u8; 2] {
+        match self {
+            Safety::None => [0, 0],
+            Safety::Cell => [1, 0],
+            Safety::Opening => [2, 0],
+            Safety::Radius(r) => [3, r],
+        }
+    }
+
+    pub(crate) fn decode(b: [u8; 2]) -> Result<Self, String> {
+        Ok(match b {
+            [0, _] => Safety::None,
+            [1, _] => Safety::Cell,
+            [2, _] => Safety::Opening,
+            [3, r] => Safety::Radius(r),
+            [k, _] => return Err(format!("未知的保护策略：{k}")),
+        })
+    }
+}
+
+/// 接受`none`、`cell`、`opening`或表示半径的整数
+impl std::str::FromStr for Safety {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "none" => Safety::None,
+            "cell" => Safety::Cell,
+            "opening" => Safety::Opening,
+            r => Safety::Radius(r.parse().map_err(|_| format!("未知的保护策略：{s}"))?),
+        })
+    }
+}
+
+/// # 地图
+/// ### Serde
+/// 表示为`{"width": u8, "height": u8, "count": u16, "cells": [Cell..]}`，
+/// `cells`按行排列，包含布局与进度，单元格见`Cell`。
+/// 尚未布置地雷时`cells`全部为空白。
+#[derive(Clone)]
 pub struct MineMap {
     // u8::MAX ** 2 < u16::MAX
     pub count: u16,
@@ -90,6 +186,58 @@ pub struct MineMap {
     pub map: Vec<u8>,
     blanks: Vec<HashSet<usize>>,
 }
+#[cfg(feature = "serde")]
+impl serde::Serialize for MineMap {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut st = s.serialize_struct("MineMap", 4)?;
+        st.serialize_field("width", &self.width)?;
+        st.serialize_field("height", &self.height)?;
+        st.serialize_field("count", &self.count)?;
+        st.serialize_field("cells", &self.iter().collect::<Vec<_>>())?;
+        st.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for MineMap {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+        #[derive(serde::Deserialize)]
+        struct Repr {
+            width: u8,
+            height: u8,
+            count: u16,
+            cells: Vec<Cell>,
+        }
+        let r = Repr::deserialize(d)?;
+        if r.width < 2 || r.height < 2 {
+            return Err(D::Error::custom(format!(
+                "区域太小：{}x{}",
+                r.width, r.height
+            )));
+        }
+        if r.cells.len() != r.width as usize * r.height as usize {
+            return Err(D::Error::custom("单元格数量与宽高不符！"));
+        }
+        // 尚未布置地雷；没有地雷的布局`count`为0
+        if r.count > 0 && !r.cells.iter().any(|c| c.is_mine()) {
+            return Self::new(r.count, r.width, r.height).map_err(D::Error::custom);
+        }
+        let mut raw = Vec::with_capacity(r.cells.len() + 2);
+        raw.extend([r.width, r.height]);
+        raw.extend(r.cells.iter().map(|c| c.0));
+        let mm = Self::by(raw, true).map_err(D::Error::custom)?;
+        if mm.count != r.count {
+            return Err(D::Error::custom(format!(
+                "地雷数不符：count为{}，实际{}",
+                r.count, mm.count
+            )));
+        }
+        Ok(mm)
+    }
+}
+
 pub struct MinesIter<'a> {
     map: &'a Vec<u8>,
     idx: usize,
@@ -106,11 +254,19 @@ impl MineMap {
     /// # Argument
     /// - map `[宽width, 高height, 数据data..]`
     /// - hold_stat 是否保留状态
+    /// # Errors
+    /// - 数据太短、区域小于2x2，或数据长度与宽高不符
     pub fn by(mut map: Vec<u8>, hold_stat: bool) -> Result<Self, String> {
         if map.len() < 6 {
             return Err("输入数据太短！[宽, 高, 数据..]".to_string());
         }
-        // TODO: 验证数据有效性
+        let (w, h) = (map[0], map[1]);
+        if w < 2 || h < 2 {
+            return Err("请设置更大的区域！".to_string());
+        }
+        if map.len() - 2 != w as usize * h as usize {
+            return Err(format!("数据长度为{}，与{w}x{h}不符！", map.len() - 2));
+        }
         let mut count = 0;
         if hold_stat {
             count = map.iter().skip(2).filter(|&v| v & 0x1f > 8).count() as u16;
@@ -170,7 +326,7 @@ impl MineMap {
         )?)?))
     }
 
-    pub fn iter(&self) -> MinesIter {
+    pub fn iter(&self) -> MinesIter<'_> {
         MinesIter {
             map: &self.map,
             idx: 0,
@@ -182,52 +338,178 @@ impl MineMap {
         self.get(x as usize, y as usize)
     }
 
-    /// 刷新地雷
-    fn shuffle(&mut self) {
-        let size = self.width as usize * self.height as usize;
+    /// 布置地雷
+    /// - 先从安全区以外的单元格中抽取，O(size)且必然结束
+    /// - 安全区以外不足时，其余地雷布置在安全区中，但不会布置在`safe[0]`
+    fn shuffle<R: Rng>(&mut self, safe: &[usize], dist: &Distribution, rng: &mut R) {
+        let size = self.map.len();
         let c = self.count as usize;
-        if c == 0 || size < c {
+        self.map.fill(0);
+        if c == 0 || size <= c {
+            self.map[..c.min(size)].fill(9);
             return;
         }
-        // self.stat.fill(0);
-        self.map.fill(0);
-        self.map[..c].fill(9);
-        // 用洗牌算法布置地雷
-        let mut rng = thread_rng();
-        self.map.shuffle(&mut rng);
-        // TODO: 在结束前对洗牌结果添加一些评判
+        let mut excluded = vec![false; size];
+        for &i in safe.iter().filter(|&&i| i < size) {
+            excluded[i] = true;
+        }
+        let mut cells: Vec<usize> = (0..size).filter(|&i| !excluded[i]).collect();
+        if cells.len() < c {
+            let mut rest: Vec<usize> = safe.iter().skip(1).copied().collect();
+            rest.sort_unstable();
+            rest.dedup();
+            rest.retain(|&i| i < size && Some(&i) != safe.first());
+            let (extra, _) = rest.partial_shuffle(rng, c - cells.len());
+            cells.extend(extra.iter());
+        }
+        if *dist != Distribution::Uniform {
+            let (w, h, _) = self.my_size();
+            for i in dist.pick(&cells, c, w, h, rng) {
+                self.map[i] = 9;
+            }
+            return;
+        }
+        // 部分洗牌，返回的切片即为地雷
+        let (mines, _) = cells.partial_shuffle(rng, c);
+        for &i in mines.iter() {
+            self.map[i] = 9;
+        }
     }
 
-    /// 设置安全区
-    fn ignore(&mut self, ignore: Option<Loc>) {
-        let Some(c) = ignore else { return };
-        let (x, y, (w, h, size)) = (c.0 as usize, c.1 as usize, self.my_size());
+    /// 安全区：首次打开的单元格及其周围，首个元素为该单元格
+    fn safe_area(&self, ignore: Option<Loc>, safety: Safety) -> Vec<usize> {
+        let (Some(Loc(x, y)), Some(r)) = (ignore, safety.radius()) else {
+            return Vec::new();
+        };
+        let (w, h, _) = self.my_size();
+        let (x, y, r) = (x as usize, y as usize, r as usize);
         let Some(c) = loc_to_idx(x, y, w, h) else {
-            return;
+            return Vec::new();
         };
-        let mut rng = thread_rng();
-        let mut area = Vec::from(get_around_index(c, w, h));
-        area.push(c);
-        for &a in &area {
-            if a > size || self.map[a] == 0 {
-                continue;
-            }
-            loop {
-                let i = rng.gen_range(0..size);
-                if !area.contains(&i) && self.map[i] == 0 {
-                    self.map[i] = 9;
-                    break;
+        let mut area = vec![c];
+        for ay in y.saturating_sub(r)..=(y + r).min(h - 1) {
+            for ax in x.saturating_sub(r)..=(x + r).min(w - 1) {
+                if (ax, ay) != (x, y) {
+                    area.push(ay * w + ax);
                 }
             }
-            self.map[a] = 0;
         }
+        area
     }
 
+    /// 开局，首次打开的位置周围一圈无雷
     pub fn new_game(&mut self, ignore: Option<Loc>) {
-        self.shuffle();
-        // 设置安全区
-        self.ignore(ignore);
-        // 设置地雷警示数值
+        self.new_game_with(ignore, Safety::Opening, None);
+    }
+
+    /// 基于种子开局
+    /// - 种子与安全区相同时，得到的布局相同
+    /// - 种子经`ChaCha8Rng`展开，不随平台与rand版本变化
+    pub fn new_game_by_seed(&mut self, ignore: Option<Loc>, seed: u64) {
+        self.new_game_with(ignore, Safety::Opening, Some(seed));
+    }
+
+    /// 按保护策略开局
+    /// # Argument
+    /// - ignore 首次打开的位置
+    /// - safety 保护策略，安全区以外放不下全部地雷时，见`new_game_by_area`
+    /// - seed 为`None`时随机开局
+    pub fn new_game_with(&mut self, ignore: Option<Loc>, safety: Safety, seed: Option<u64>) {
+        let safe = self.safe_area(ignore, safety);
+        self.place(&safe, seed);
+    }
+
+    /// 以自定义的安全区开局
+    /// # Argument
+    /// - safe 不布置地雷的单元格，越界的坐标被忽略；地雷多于安全区以外的单元格时，
+    ///   其余地雷布置在安全区中，但首个单元格始终安全
+    /// - seed 为`None`时随机开局
+    pub fn new_game_by_area(&mut self, safe: &[Loc], seed: Option<u64>) {
+        let (w, h, _) = self.my_size();
+        let safe: Vec<usize> = safe
+            .iter()
+            .filter_map(|&Loc(x, y)| loc_to_idx(x as usize, y as usize, w, h))
+            .collect();
+        self.place(&safe, seed);
+    }
+
+    /// 按条件筛选布局后开局，见`quality`
+    /// # Argument
+    /// - ignore, safety, seed 见`new_game_with`，种子相同时筛选结果相同
+    /// - filter 筛选条件，尝试次数达到上限时保留最后一次生成的布局
+    pub fn new_game_filtered(
+        &mut self,
+        ignore: Option<Loc>,
+        safety: Safety,
+        seed: Option<u64>,
+        filter: &Filter,
+    ) -> Report {
+        let safe = self.safe_area(ignore, safety);
+        let mut rng = match seed {
+            Some(seed) => ChaCha8Rng::seed_from_u64(seed),
+            None => ChaCha8Rng::from_entropy(),
+        };
+        let mut attempts = 0;
+        let (accepted, metrics) = loop {
+            attempts += 1;
+            self.shuffle(&safe, &Distribution::Uniform, &mut rng);
+            self.set_warn();
+            // 上一次布局的空白分组已失效
+            self.blanks.clear();
+            let m = quality::measure(self, ignore, filter.max_guesses.is_some());
+            if filter.accepts(&m) {
+                break (true, m);
+            }
+            if attempts >= filter.max_attempts {
+                break (false, m);
+            }
+        };
+        self.group_blank();
+        Report {
+            attempts,
+            accepted,
+            metrics,
+        }
+    }
+
+    /// 按指定的分布开局，见`distribution`
+    /// # Argument
+    /// - ignore, safety, seed 见`new_game_with`
+    /// # Errors
+    /// - 分布的参数无效，此时地图不变
+    pub fn new_game_by_distribution(
+        &mut self,
+        ignore: Option<Loc>,
+        safety: Safety,
+        seed: Option<u64>,
+        dist: &Distribution,
+    ) -> Result<(), String> {
+        dist.validate(self.width, self.height)?;
+        let safe = self.safe_area(ignore, safety);
+        match seed {
+            Some(seed) => self.generate(&safe, dist, &mut ChaCha8Rng::seed_from_u64(seed)),
+            None => self.generate(&safe, dist, &mut thread_rng()),
+        }
+        Ok(())
+    }
+
+    fn place(&mut self, safe: &[usize], seed: Option<u64>) {
+        let dist = Distribution::Uniform;
+        match seed {
+            Some(seed) => self.generate(safe, &dist, &mut ChaCha8Rng::seed_from_u64(seed)),
+            None => self.generate(safe, &dist, &mut thread_rng()),
+        }
+    }
+
+    fn generate<R: Rng>(&mut self, safe: &[usize], dist: &Distribution, rng: &mut R) {
+        self.shuffle(safe, dist, rng);
+        self.set_warn();
+        // 分组收集空白区域
+        self.group_blank();
+    }
+
+    /// 设置地雷警示数值，地雷须事先标记为9
+    fn set_warn(&mut self) {
         let (w, h, size) = self.my_size();
         for i in 0..size {
             if self.map[i] < 9 {
@@ -240,11 +522,36 @@ impl MineMap {
                 }
             }
         }
-        // let tt = std::time::Instant::now();
-        // 分组收集空白区域
-        self.group_blank();
-        // let tt = tt.elapsed().as_micros();
-        // println!("grouping times: {}ms({tt}us)", tt / 1000);
+    }
+
+    /// 基于地雷下标导入布局，警示数值自动计算
+    /// # Argument
+    /// - mines 地雷下标，按行排列，可以为空
+    pub fn by_mines(width: u8, height: u8, mines: &[usize]) -> Result<Self, String> {
+        if width < 2 || height < 2 {
+            return Err("请设置更大的区域！".to_string());
+        }
+        let size = width as usize * height as usize;
+        if mines.len() >= size {
+            return Err("请减少地雷数量！".to_string());
+        }
+        let mut mm = Self {
+            count: mines.len() as u16,
+            width,
+            height,
+            map: vec![0; size],
+            blanks: Vec::with_capacity(size / 32),
+        };
+        for &i in mines {
+            match mm.map.get_mut(i) {
+                Some(v @ 0) => *v = 9,
+                Some(_) => return Err(format!("地雷下标重复：{i}")),
+                None => return Err(format!("地雷下标越界：{i}")),
+            }
+        }
+        mm.set_warn();
+        mm.group_blank();
+        Ok(mm)
     }
 
     /// 重置进度：清除开关、标记状态
@@ -254,57 +561,23 @@ impl MineMap {
         }
     }
 
+    /// 完整布局的文本，地雷为`-`，见`render::Theme::LEGACY`
     pub fn format_str(&self) -> String {
-        const NUMS: &[u8; 9] = b" 12345678";
-        let (w, h, size) = self.my_size();
-        let mut buf = String::with_capacity(size * 2 + h);
-        let mut ln = 0;
-        for v in &self.map {
-            buf.push(' ');
-            let v = Cell(*v).get_warn() as usize;
-            if v <= 8 {
-                buf.push(NUMS[v] as char);
-            } else {
-                buf.push('-');
-            }
-            if ln < w - 1 {
-                ln += 1;
-            } else {
-                buf.push('\n');
-                ln = 0;
-            }
+        Plain {
+            theme: Theme::LEGACY,
+            layout: true,
+            ..Default::default()
         }
-        buf
+        .render(self)
     }
 
+    /// 玩家视角的文本，未打开为`·`，插旗为`@`，见`render::Theme::LEGACY`
     pub fn format_stat_str(&self) -> String {
-        const NUMS: &[u8; 9] = b" 12345678";
-        let (w, h, size) = self.my_size();
-        let mut buf = String::with_capacity(size * 2 + h);
-        let mut ln = 0;
-        for v in &self.map {
-            buf.push(' ');
-            let c = Cell(*v);
-            if c.is_reveal() {
-                let w = c.get_warn() as usize;
-                if w < 8 {
-                    buf.push(NUMS[w] as char);
-                } else {
-                    buf.push('+');
-                }
-            } else if c.is_flagged() {
-                buf.push('@');
-            } else {
-                buf.push('·');
-            }
-            if ln < w - 1 {
-                ln += 1;
-            } else {
-                buf.push('\n');
-                ln = 0;
-            }
+        Plain {
+            theme: Theme::LEGACY,
+            ..Default::default()
         }
-        buf
+        .render(self)
     }
 
     /// 找到空白区域
@@ -404,6 +677,40 @@ impl MineMap {
             .any(|c| !c.is_reveal() && !c.is_mine())
     }
 
+    /// 统计3BV：不借助连锁打开的情况下，完成本局所需的最少点击数
+    /// - 每片空白区域计1次
+    /// - 不与空白相邻的数字单元格各计1次
+    pub fn count_3bv(&self) -> usize {
+        let (w, h, size) = self.my_size();
+        let mut marked = vec![false; size];
+        let mut stack = Vec::new();
+        let mut count = 0;
+        for i in 0..size {
+            if marked[i] || !Cell(self.map[i]).is_empty() {
+                continue;
+            }
+            // 连锁打开整片空白，连同边缘的数字一并标记
+            count += 1;
+            marked[i] = true;
+            stack.push(i);
+            while let Some(c) = stack.pop() {
+                for a in around_index(c, w, h) {
+                    if marked[a] {
+                        continue;
+                    }
+                    marked[a] = true;
+                    if Cell(self.map[a]).is_empty() {
+                        stack.push(a);
+                    }
+                }
+            }
+        }
+        count
+            + (0..size)
+                .filter(|&i| !marked[i] && !Cell(self.map[i]).is_mine())
+                .count()
+    }
+
     /// 打开所有地雷
     pub fn reveal_all_mines(&mut self) {
         for v in self.map.iter_mut() {
@@ -505,6 +812,17 @@ impl MineMap {
         count
     }
 
+    /// 导出玩家视角的数据
+    /// - 未打开的单元格隐藏周围地雷数，仅保留插旗状态
+    /// # Returns
+    /// - `[数据data..]`，不含宽高
+    pub fn view(&self) -> Vec<u8> {
+        self.map
+            .iter()
+            .map(|&v| if Cell(v).is_reveal() { v } else { v & 0x40 })
+            .collect()
+    }
+
     /// 导出布局数据
     /// # Argument
     /// - `hold_stat` 是否保留状态
@@ -0,0 +1,180 @@
+//! # 布局评判
+//! 统计布局的各项指标，并按条件筛选。开局时配合`MineMap::new_game_filtered`
+//! 反复生成布局，直到满足全部条件或达到尝试次数上限。
+//!
+//! 被迫猜测数的统计方法：从首次打开的位置开始，每轮用`solver::probabilities`
+//! 找出确定安全的单元格全部打开；没有时计一次猜测，打开概率最低的安全单元格。
+//! 猜测时借助真实布局避开地雷，相当于假设每次都猜对，
+//! 因此是近似值：实际对局中猜错即结束，不会再有后续的猜测。
+
+use crate::{
+    cell::Cell,
+    location::Loc,
+    mmap::{around_index, MineMap},
+    solver,
+};
+
+/// 布局的指标
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Metrics {
+    pub bbbv: usize,
+    /// 空白区域（开口）数
+    pub openings: usize,
+    /// 相连（含斜向）的最大地雷群的地雷数
+    pub max_cluster: usize,
+    /// 被迫猜测数（假设每次都猜对的近似值），未统计或枚举规模过大时为`None`
+    pub guesses: Option<usize>,
+}
+
+/// 筛选条件，`None`表示不限制
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Filter {
+    pub min_3bv: Option<usize>,
+    pub max_3bv: Option<usize>,
+    pub min_openings: Option<usize>,
+    pub max_openings: Option<usize>,
+    /// 最大被迫猜测数，无法统计的布局视为不满足
+    pub max_guesses: Option<usize>,
+    /// 地雷群的最大地雷数
+    pub max_cluster: Option<usize>,
+    /// 尝试次数上限
+    pub max_attempts: usize,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Self {
+            min_3bv: None,
+            max_3bv: None,
+            min_openings: None,
+            max_openings: None,
+            max_guesses: None,
+            max_cluster: None,
+            max_attempts: 1000,
+        }
+    }
+}
+
+impl Filter {
+    /// 是否满足全部条件
+    pub fn accepts(&self, m: &Metrics) -> bool {
+        let within = |v: usize, min: Option<usize>, max: Option<usize>| {
+            min.unwrap_or(0) <= v && v <= max.unwrap_or(usize::MAX)
+        };
+        within(m.bbbv, self.min_3bv, self.max_3bv)
+            && within(m.openings, self.min_openings, self.max_openings)
+            && within(m.max_cluster, None, self.max_cluster)
+            && match self.max_guesses {
+                Some(g) => m.guesses.is_some_and(|v| v <= g),
+                None => true,
+            }
+    }
+}
+
+/// 筛选的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Report {
+    /// 生成布局的次数
+    pub attempts: usize,
+    /// 是否满足条件，不满足时保留最后一次生成的布局
+    pub accepted: bool,
+    /// 最终布局的指标
+    pub metrics: Metrics,
+}
+
+/// 统计布局的指标
+/// # Argument
+/// - `start` 首次打开的位置，用于统计被迫猜测数
+/// - `guesses` 是否统计被迫猜测数，耗时较长
+pub fn measure(map: &MineMap, start: Option<Loc>, guesses: bool) -> Metrics {
+    Metrics {
+        bbbv: map.count_3bv(),
+        openings: openings(map),
+        max_cluster: max_cluster(map),
+        guesses: if guesses {
+            count_guesses(map, start)
+        } else {
+            None
+        },
+    }
+}
+
+/// 满足`pick`的单元格按八连通分组，返回各组的大小
+fn groups(map: &MineMap, pick: impl Fn(Cell) -> bool) -> Vec<usize> {
+    let (w, h) = (map.width as usize, map.height as usize);
+    let cells: Vec<Cell> = map.iter().collect();
+    let mut seen = vec![false; cells.len()];
+    let mut out = Vec::new();
+    let mut stack = Vec::new();
+    for i in 0..cells.len() {
+        if seen[i] || !pick(cells[i]) {
+            continue;
+        }
+        seen[i] = true;
+        stack.push(i);
+        let mut n = 0;
+        while let Some(c) = stack.pop() {
+            n += 1;
+            for a in around_index(c, w, h) {
+                if !seen[a] && pick(cells[a]) {
+                    seen[a] = true;
+                    stack.push(a);
+                }
+            }
+        }
+        out.push(n);
+    }
+    out
+}
+
+fn openings(map: &MineMap) -> usize {
+    groups(map, |c| c.is_empty()).len()
+}
+
+fn max_cluster(map: &MineMap) -> usize {
+    groups(map, |c| c.is_mine()).into_iter().max().unwrap_or(0)
+}
+
+/// 被迫猜测数，猜测时借助真实布局选择安全单元格，见模块说明
+fn count_guesses(map: &MineMap, start: Option<Loc>) -> Option<usize> {
+    let w = map.width as usize;
+    let mut m = map.clone();
+    m.reset_progress();
+    let mut guesses = 0;
+    let first = start
+        .map(|Loc(x, y)| y as usize * w + x as usize)
+        .filter(|&i| m.map.get(i).is_some_and(|&v| !Cell(v).is_mine()));
+    let first = match first {
+        Some(i) => i,
+        None => {
+            // 没有安全的起点时，首次打开本身就是猜测
+            guesses += 1;
+            let cells: Vec<Cell> = m.iter().collect();
+            cells
+                .iter()
+                .position(|c| c.is_empty())
+                .or_else(|| cells.iter().position(|c| !c.is_mine()))?
+        }
+    };
+    m.reveal(first % w, first / w);
+    while !m.is_all_reveal() {
+        let p = solver::probabilities(m.width, m.height, m.count, &m.view())?;
+        let hidden: Vec<usize> = (0..p.len())
+            .filter(|&i| !Cell(m.map[i]).is_reveal())
+            .collect();
+        let safe: Vec<usize> = hidden.iter().copied().filter(|&i| p[i] == 0.0).collect();
+        if safe.is_empty() {
+            guesses += 1;
+            let i = hidden
+                .into_iter()
+                .filter(|&i| !Cell(m.map[i]).is_mine())
+                .min_by(|&a, &b| p[a].total_cmp(&p[b]))?;
+            m.reveal(i % w, i / w);
+        } else {
+            for i in safe {
+                m.reveal(i % w, i / w);
+            }
+        }
+    }
+    Some(guesses)
+}
@@ -0,0 +1,362 @@
+//! # 文本渲染
+//! `Renderer`将地图渲染为文本，后端可互换：
+//! - `Plain` 纯文本
+//! - `Ansi` 256色终端
+//! - `Unicode` 带边框，默认使用宽字符/emoji
+//! - `Html` HTML表格
+//!
+//! 字形由`Theme`决定。坐标标注与命令行一致：列号为x，行号为y，均从0开始。
+
+use std::fmt::Write;
+
+use crate::{cell::Cell, mmap::MineMap};
+
+/// 单元格显示的内容
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Glyph {
+    Hidden,
+    Flag,
+    Mine,
+    /// 周围地雷数，0为空白
+    Warn(u8),
+}
+
+impl Glyph {
+    /// # Argument
+    /// - `layout` 忽略进度，显示完整布局
+    pub fn of(c: Cell, layout: bool) -> Self {
+        if layout {
+            if c.is_mine() {
+                Glyph::Mine
+            } else {
+                Glyph::Warn(c.get_warn())
+            }
+        } else if c.is_flagged() {
+            Glyph::Flag
+        } else if !c.is_reveal() {
+            Glyph::Hidden
+        } else if c.is_mine() {
+            Glyph::Mine
+        } else {
+            Glyph::Warn(c.get_warn())
+        }
+    }
+}
+
+/// 字形主题
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub hidden: &'static str,
+    pub flag: &'static str,
+    pub mine: &'static str,
+    /// 数字0~8
+    pub warn: [&'static str; 9],
+    /// 每个字形在终端中占的列数
+    pub width: usize,
+}
+
+impl Theme {
+    /// 纯ASCII
+    pub const ASCII: Theme = Theme {
+        hidden: ".",
+        flag: "F",
+        mine: "*",
+        warn: [" ", "1", "2", "3", "4", "5", "6", "7", "8"],
+        width: 1,
+    };
+    /// `MineMap::format_str`与`MineMap::format_stat_str`使用的字形
+    pub const LEGACY: Theme = Theme {
+        hidden: "·",
+        flag: "@",
+        mine: "-",
+        ..Theme::ASCII
+    };
+    /// 单宽Unicode符号
+    pub const UNICODE: Theme = Theme {
+        hidden: "■",
+        flag: "⚑",
+        mine: "✹",
+        ..Theme::ASCII
+    };
+    /// emoji与全角数字，每个字形占两列
+    pub const EMOJI: Theme = Theme {
+        hidden: "⬜",
+        flag: "🚩",
+        mine: "💣",
+        warn: ["　", "１", "２", "３", "４", "５", "６", "７", "８"],
+        width: 2,
+    };
+
+    pub fn glyph(&self, g: Glyph) -> &'static str {
+        match g {
+            Glyph::Hidden => self.hidden,
+            Glyph::Flag => self.flag,
+            Glyph::Mine => self.mine,
+            Glyph::Warn(v) => self.warn[(v as usize).min(8)],
+        }
+    }
+}
+
+/// # 渲染器
+pub trait Renderer {
+    fn render(&self, map: &MineMap) -> String;
+}
+
+/// 纯文本，每个单元格前加一个空格，每行以换行结尾
+#[derive(Debug, Clone, Copy)]
+pub struct Plain {
+    pub theme: Theme,
+    /// 标注行号、列号
+    pub labels: bool,
+    /// 忽略进度，显示完整布局
+    pub layout: bool,
+}
+
+impl Default for Plain {
+    fn default() -> Self {
+        Self {
+            theme: Theme::ASCII,
+            labels: false,
+            layout: false,
+        }
+    }
+}
+
+impl Renderer for Plain {
+    fn render(&self, map: &MineMap) -> String {
+        grid(
+            map,
+            &self.theme,
+            self.labels,
+            self.layout,
+            false,
+            |buf, _, s| buf.push_str(s),
+        )
+    }
+}
+
+/// 256色终端，数字按经典配色着色
+#[derive(Debug, Clone, Copy)]
+pub struct Ansi {
+    pub theme: Theme,
+    pub labels: bool,
+    pub layout: bool,
+}
+
+impl Default for Ansi {
+    fn default() -> Self {
+        Self {
+            theme: Theme::UNICODE,
+            labels: false,
+            layout: false,
+        }
+    }
+}
+
+/// 数字1~8的前景色（256色）
+const ANSI_COLORS: [u8; 8] = [21, 28, 196, 18, 88, 30, 16, 244];
+
+impl Renderer for Ansi {
+    fn render(&self, map: &MineMap) -> String {
+        grid(
+            map,
+            &self.theme,
+            self.labels,
+            self.layout,
+            false,
+            |buf, g, s| {
+                let _ = match g {
+                    Glyph::Hidden => write!(buf, "\x1b[38;5;244m{s}\x1b[0m"),
+                    Glyph::Flag => write!(buf, "\x1b[1;38;5;196m{s}\x1b[0m"),
+                    Glyph::Mine => write!(buf, "\x1b[38;5;231;48;5;160m{s}\x1b[0m"),
+                    Glyph::Warn(0) => write!(buf, "{s}"),
+                    Glyph::Warn(v) => write!(
+                        buf,
+                        "\x1b[1;38;5;{}m{s}\x1b[0m",
+                        ANSI_COLORS[(v as usize).min(8) - 1]
+                    ),
+                };
+            },
+        )
+    }
+}
+
+/// 以框线字符绘制边框
+#[derive(Debug, Clone, Copy)]
+pub struct Unicode {
+    pub theme: Theme,
+    pub labels: bool,
+    pub layout: bool,
+}
+
+impl Default for Unicode {
+    fn default() -> Self {
+        Self {
+            theme: Theme::EMOJI,
+            labels: false,
+            layout: false,
+        }
+    }
+}
+
+impl Renderer for Unicode {
+    fn render(&self, map: &MineMap) -> String {
+        grid(
+            map,
+            &self.theme,
+            self.labels,
+            self.layout,
+            true,
+            |buf, _, s| buf.push_str(s),
+        )
+    }
+}
+
+/// HTML表格，样式内联，可直接嵌入页面
+#[derive(Debug, Clone, Copy)]
+pub struct Html {
+    pub theme: Theme,
+    pub labels: bool,
+    pub layout: bool,
+}
+
+impl Default for Html {
+    fn default() -> Self {
+        Self {
+            theme: Theme::EMOJI,
+            labels: false,
+            layout: false,
+        }
+    }
+}
+
+/// 数字1~8的颜色
+const HTML_COLORS: [&str; 8] = [
+    "#0000ff", "#008000", "#ff0000", "#000080", "#800000", "#008080", "#000000", "#808080",
+];
+
+impl Renderer for Html {
+    fn render(&self, map: &MineMap) -> String {
+        let w = map.width as usize;
+        let mut buf = String::with_capacity(map.map.len() * 64);
+        buf.push_str(
+            "<table class=\"mines\" style=\"border-collapse:collapse;font-family:monospace;text-align:center\">\n",
+        );
+        if self.labels {
+            buf.push_str("<tr><th></th>");
+            for x in 0..w {
+                let _ = write!(buf, "<th>{x}</th>");
+            }
+            buf.push_str("</tr>\n");
+        }
+        for (i, c) in map.iter().enumerate() {
+            let (x, y) = (i % w, i / w);
+            if x == 0 {
+                buf.push_str("<tr>");
+                if self.labels {
+                    let _ = write!(buf, "<th>{y}</th>");
+                }
+            }
+            let g = Glyph::of(c, self.layout);
+            let style = match g {
+                Glyph::Hidden | Glyph::Flag => "background:#c0c0c0;border-style:outset".to_string(),
+                Glyph::Mine => "background:#ff8080".to_string(),
+                Glyph::Warn(0) => String::new(),
+                Glyph::Warn(v) => format!(
+                    "color:{};font-weight:bold",
+                    HTML_COLORS[(v as usize).min(8) - 1]
+                ),
+            };
+            let _ = write!(
+                buf,
+                "<td style=\"width:1.6em;height:1.6em;border:1px solid #808080;{style}\">{}</td>",
+                escape(self.theme.glyph(g))
+            );
+            if x == w - 1 {
+                buf.push_str("</tr>\n");
+            }
+        }
+        buf.push_str("</table>\n");
+        buf
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// 十进制位数
+fn digits(v: usize) -> usize {
+    v.max(1).ilog10() as usize + 1
+}
+
+/// 按行输出文本网格
+/// - `paint` 写入一个单元格的字形，不含对齐用的空格
+fn grid(
+    map: &MineMap,
+    theme: &Theme,
+    labels: bool,
+    layout: bool,
+    frame: bool,
+    paint: impl Fn(&mut String, Glyph, &str),
+) -> String {
+    let (w, h) = (map.width as usize, map.height as usize);
+    // 列宽需容纳列号
+    let cw = if labels {
+        theme.width.max(digits(w.saturating_sub(1)))
+    } else {
+        theme.width
+    };
+    let rw = if labels {
+        digits(h.saturating_sub(1))
+    } else {
+        0
+    };
+    let inner = w * (cw + 1) + 1;
+    let mut buf = String::with_capacity((w * (cw + 1) + rw + 4) * (h + 3) * 2);
+    let indent = |buf: &mut String| {
+        buf.push_str(&" ".repeat(rw));
+        if frame {
+            buf.push(' ');
+        }
+    };
+    if labels {
+        indent(&mut buf);
+        if frame {
+            buf.push(' ');
+        }
+        for x in 0..w {
+            let _ = write!(buf, " {x:>cw$}");
+        }
+        buf.push('\n');
+    }
+    if frame {
+        indent(&mut buf);
+        let _ = writeln!(buf, "┌{}┐", "─".repeat(inner));
+    }
+    for y in 0..h {
+        if labels {
+            let _ = write!(buf, "{y:>rw$}");
+        }
+        if frame {
+            buf.push_str(" │");
+        }
+        for x in 0..w {
+            let g = Glyph::of(map.get(x, y).unwrap_or(Cell(0)), layout);
+            buf.push(' ');
+            buf.push_str(&" ".repeat(cw - theme.width));
+            paint(&mut buf, g, theme.glyph(g));
+        }
+        if frame {
+            buf.push_str(" │");
+        }
+        buf.push('\n');
+    }
+    if frame {
+        indent(&mut buf);
+        let _ = writeln!(buf, "└{}┘", "─".repeat(inner));
+    }
+    buf
+}
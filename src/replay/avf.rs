@@ -0,0 +1,59 @@
+//! # Minesweeper Arbiter 录像（.avf）
+//! 支持的结构（多字节整数为大端序）：
+//! - `version: u8`，其后4字节保留
+//! - `mode: u8` 3 初级8x8/10，4 中级16x16/40，5 高级30x16/99，6 自定义
+//! - 自定义时：`width - 1: u8`, `height - 1: u8`, `mines: u16`
+//! - `mines` 个 `(row: u8, col: u8)`，从1开始
+//! - 玩家信息等文本，以`[`开头、`]`结尾，整段跳过
+//! - 鼠标事件，每条8字节：`kind: u8, x: u16, y: u16, ms: u24`，`kind`为0时结束
+//!   - `kind` 1 移动，3 左键按下，5 左键松开，9 右键按下，17 右键松开，33 中键按下，65 中键松开
+
+use super::{Button, Mouse, MouseEvent, Reader, Replay};
+
+pub fn parse(data: &[u8]) -> Result<Replay, String> {
+    let mut r = Reader::new(data);
+    r.u8()?;
+    r.take(4)?;
+    let (width, height, count) = match r.u8()? {
+        3 => (8, 8, 10),
+        4 => (16, 16, 40),
+        5 => (30, 16, 99),
+        6 => {
+            let w = r.u8()?.checked_add(1).ok_or("自定义宽度无效！")?;
+            let h = r.u8()?.checked_add(1).ok_or("自定义高度无效！")?;
+            (w, h, r.u16()?)
+        }
+        m => return Err(format!("未知的AVF模式：{m}")),
+    };
+    let mut mines = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let (row, col) = (r.u8()?, r.u8()?);
+        if row == 0 || col == 0 || row > height || col > width {
+            return Err(format!("地雷坐标越界：第{row}行第{col}列"));
+        }
+        mines.push((row - 1) as usize * width as usize + (col - 1) as usize);
+    }
+    r.skip_past(b'[')?;
+    r.skip_past(b']')?;
+
+    let mut events = Vec::new();
+    while !r.is_end() {
+        let kind = r.u8()?;
+        if kind == 0 {
+            break;
+        }
+        let (x, y, ms) = (r.u16()?, r.u16()?, r.u24()?);
+        let mouse = match kind {
+            1 => Mouse::Move,
+            3 => Mouse::Press(Button::Left),
+            5 => Mouse::Release(Button::Left),
+            9 => Mouse::Press(Button::Right),
+            17 => Mouse::Release(Button::Right),
+            33 => Mouse::Press(Button::Middle),
+            65 => Mouse::Release(Button::Middle),
+            k => return Err(format!("未知的AVF鼠标事件：{k}")),
+        };
+        events.push(MouseEvent { ms, mouse, x, y });
+    }
+    super::build(width, height, &mines, &events)
+}
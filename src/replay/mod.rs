@@ -0,0 +1,246 @@
+//! # 录像
+//! 本库的录像由布局与操作序列组成，
+//! 其他扫雷程序的录像先解析为鼠标事件，再转换为操作序列。
+
+pub mod avf;
+pub mod mrpl;
+pub mod mvf;
+pub mod rmv;
+pub mod verify;
+
+use crate::{
+    game::{Action, Game},
+    location::Loc,
+    mmap::{MineMap, Safety},
+};
+
+/// 录像中的一步操作
+/// ### Serde
+/// 表示为`{"ms": u32, "action": Action}`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Event {
+    /// 距开局的毫秒数
+    pub ms: u32,
+    pub action: Action,
+}
+
+/// # 录像
+/// ### Serde
+/// 表示为`{"map": MineMap, "seed": u64?, "safety": Safety, "events": [Event..]}`，
+/// 缺少`safety`时为默认策略
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Replay {
+    /// 布局，不含进度
+    pub map: MineMap,
+    pub seed: Option<u64>,
+    /// 由种子生成布局时的保护策略
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub safety: Safety,
+    pub events: Vec<Event>,
+}
+
+impl Replay {
+    /// 由对局生成录像
+    pub fn from_game(game: &Game) -> Self {
+        let mut map = game.map().clone();
+        map.reset_progress();
+        Self {
+            map,
+            seed: game.seed(),
+            safety: game.safety(),
+            events: game.history().to_vec(),
+        }
+    }
+
+    /// 按开头的标识识别格式并解析，无法识别的视为AVF
+    pub fn load(data: &[u8]) -> Result<Self, String> {
+        if data.starts_with(mrpl::MAGIC) {
+            mrpl::parse(data)
+        } else if data.starts_with(rmv::MAGIC) {
+            rmv::parse(data)
+        } else if data.starts_with(mvf::MAGIC) {
+            mvf::parse(data)
+        } else {
+            avf::parse(data)
+        }
+    }
+
+    /// 在布局上重放全部操作
+    pub fn play(&self) -> Game {
+        let mut game = Game::by_map(self.map.clone()).with_safety(self.safety);
+        for e in &self.events {
+            game.act(e.action);
+        }
+        game
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Button {
+    Left,
+    Right,
+    Middle,
+}
+
+/// 鼠标事件
+/// ### Serde
+/// 表示为`"move"`、`{"press": Button}`、`{"release": Button}`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Mouse {
+    Move,
+    Press(Button),
+    Release(Button),
+}
+
+/// 录像中的鼠标事件，坐标为相对棋盘左上角的像素
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MouseEvent {
+    pub ms: u32,
+    pub mouse: Mouse,
+    pub x: u16,
+    pub y: u16,
+}
+
+/// 常见扫雷程序的单元格边长（像素）
+pub const CELL_PX: u16 = 16;
+
+/// # 将鼠标事件转换为操作
+/// - 左键单独按下后松开：打开
+/// - 右键单独按下：插旗
+/// - 中键松开，或左右键同时按下后任一键松开：双击
+/// - 落在棋盘之外的事件忽略
+pub fn to_actions(ls: &[MouseEvent], width: u8, height: u8) -> Vec<Event> {
+    let mut res = Vec::new();
+    let (mut left, mut right) = (false, false);
+    // 左右键同时按下后，等待两键都松开
+    let mut chord = false;
+    for e in ls {
+        let (x, y) = (e.x / CELL_PX, e.y / CELL_PX);
+        let loc = (x < width as u16 && y < height as u16).then_some(Loc(x as u8, y as u8));
+        let mut push = |action: fn(Loc) -> Action| {
+            if let Some(loc) = loc {
+                res.push(Event {
+                    ms: e.ms,
+                    action: action(loc),
+                });
+            }
+        };
+        match e.mouse {
+            Mouse::Move => {}
+            Mouse::Press(Button::Left) => {
+                left = true;
+                chord |= right;
+            }
+            Mouse::Press(Button::Right) => {
+                right = true;
+                if left {
+                    chord = true;
+                } else {
+                    push(Action::Flag);
+                }
+            }
+            Mouse::Press(Button::Middle) => {}
+            Mouse::Release(Button::Middle) => push(Action::Chord),
+            Mouse::Release(b) => {
+                let was = if b == Button::Left {
+                    &mut left
+                } else {
+                    &mut right
+                };
+                if !*was {
+                    continue;
+                }
+                *was = false;
+                if chord {
+                    // 先松开的键触发双击
+                    if left || right {
+                        push(Action::Chord);
+                    } else {
+                        chord = false;
+                    }
+                } else if b == Button::Left {
+                    push(Action::Reveal);
+                }
+            }
+        }
+    }
+    res
+}
+
+/// 由解析得到的布局与鼠标事件生成录像
+pub(crate) fn build(
+    width: u8,
+    height: u8,
+    mines: &[usize],
+    events: &[MouseEvent],
+) -> Result<Replay, String> {
+    Ok(Replay {
+        map: MineMap::by_mines(width, height, mines)?,
+        seed: None,
+        safety: Safety::default(),
+        events: to_actions(events, width, height),
+    })
+}
+
+/// 按字节读取录像数据
+pub(crate) struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    #[inline]
+    pub(crate) fn is_end(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    pub(crate) fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let end = self.pos + n;
+        let Some(bs) = self.data.get(self.pos..end) else {
+            return Err(format!("录像数据在第{}字节处意外结束！", self.data.len()));
+        };
+        self.pos = end;
+        Ok(bs)
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn u16(&mut self) -> Result<u16, String> {
+        let b = self.take(2)?;
+        Ok(u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    pub(crate) fn u24(&mut self) -> Result<u32, String> {
+        let b = self.take(3)?;
+        Ok(u32::from_be_bytes([0, b[0], b[1], b[2]]))
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, String> {
+        let b = self.take(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    /// 跳过直到遇到指定字节，该字节一并跳过
+    pub(crate) fn skip_past(&mut self, end: u8) -> Result<(), String> {
+        while self.u8()? != end {}
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+//! # 本库的录像格式（.mrpl）
+//! 多字节整数为大端序，依次为：
+//! - `magic: [u8; 4]` 固定为`MRPL`
+//! - `version: u8`
+//! - `has_seed: u8`, `seed: u64`
+//! - `safety: [u8; 2]` 由种子生成布局时的保护策略，见`Safety`
+//! - 布局，见`share::pack`
+//! - `events: u32`，其后每条7字节：`ms: u32, kind: u8, x: u8, y: u8`
+//!   - `kind` 0 打开，1 插旗，2 双击
+//! - `crc: u32` 之前全部字节的CRC32
+
+use super::{Event, Reader, Replay};
+use crate::{
+    game::Action,
+    location::Loc,
+    mmap::Safety,
+    save::crc32,
+    share::{pack, unpack},
+};
+
+pub const MAGIC: &[u8; 4] = b"MRPL";
+pub const VERSION: u8 = 1;
+
+/// 导出录像
+/// - 布局尚未生成时失败
+pub fn export(r: &Replay) -> Result<Vec<u8>, String> {
+    let mut buf = MAGIC.to_vec();
+    buf.push(VERSION);
+    buf.push(r.seed.is_some() as u8);
+    buf.extend(r.seed.unwrap_or(0).to_be_bytes());
+    buf.extend(r.safety.encode());
+    buf.extend(pack(&r.map)?);
+    buf.extend((r.events.len() as u32).to_be_bytes());
+    for e in &r.events {
+        let kind = match e.action {
+            Action::Reveal(_) => 0,
+            Action::Flag(_) => 1,
+            Action::Chord(_) => 2,
+        };
+        let Loc(x, y) = e.action.loc();
+        buf.extend(e.ms.to_be_bytes());
+        buf.extend([kind, x, y]);
+    }
+    let crc = crc32(&buf);
+    buf.extend(crc.to_be_bytes());
+    Ok(buf)
+}
+
+pub fn parse(data: &[u8]) -> Result<Replay, String> {
+    if data.len() < 4 {
+        return Err("录像数据太短！".to_string());
+    }
+    let (body, crc) = data.split_at(data.len() - 4);
+    if crc32(body).to_be_bytes() != crc {
+        return Err("录像校验失败，数据已损坏！".to_string());
+    }
+    let mut r = Reader::new(body);
+    if r.take(4)? != MAGIC {
+        return Err("不是MRPL录像！".to_string());
+    }
+    let version = r.u8()?;
+    if version != VERSION {
+        return Err(format!("不支持的录像版本：{version}"));
+    }
+    let has_seed = r.u8()? != 0;
+    let seed = u64::from_be_bytes(r.take(8)?.try_into().unwrap_or_default());
+    let safety = Safety::decode([r.u8()?, r.u8()?])?;
+    let (w, h) = (r.u8()?, r.u8()?);
+    let bits = r.take((w as usize * h as usize).div_ceil(8))?;
+    let mut layout = vec![w, h];
+    layout.extend(bits);
+    let map = unpack(&layout)?;
+
+    let n = r.u32()?;
+    let mut events = Vec::with_capacity(n.min(1 << 16) as usize);
+    for _ in 0..n {
+        let ms = r.u32()?;
+        let (kind, loc) = (r.u8()?, Loc(r.u8()?, r.u8()?));
+        let action = match kind {
+            0 => Action::Reveal(loc),
+            1 => Action::Flag(loc),
+            2 => Action::Chord(loc),
+            k => return Err(format!("未知的操作：{k}")),
+        };
+        events.push(Event { ms, action });
+    }
+    if !r.is_end() {
+        return Err("录像数据多余！".to_string());
+    }
+    Ok(Replay {
+        map,
+        seed: has_seed.then_some(seed),
+        safety,
+        events,
+    })
+}
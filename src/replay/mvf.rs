@@ -0,0 +1,54 @@
+//! # Minesweeper Clone 录像（.mvf）
+//! 支持的结构（多字节整数为大端序）：
+//! - `magic: [u8; 2]` 固定为`[0x11, 0x4D]`
+//! - `width: u8`, `height: u8`, `mines: u16`
+//! - `mines` 个 `(x: u8, y: u8)`，从1开始
+//! - 鼠标采样直到数据结束，每条8字节：`ms: u24, buttons: u8, x: u16, y: u16`
+//!   - `buttons` 为按键状态：1 左键，2 右键，4 中键；与上一条对比得到按下、松开
+
+use super::{Button, Mouse, MouseEvent, Reader, Replay};
+
+pub const MAGIC: &[u8; 2] = &[0x11, 0x4D];
+
+const BUTTONS: [(u8, Button); 3] = [(1, Button::Left), (2, Button::Right), (4, Button::Middle)];
+
+pub fn parse(data: &[u8]) -> Result<Replay, String> {
+    let mut r = Reader::new(data);
+    if r.take(2)? != MAGIC {
+        return Err("不是MVF录像！".to_string());
+    }
+    let (width, height, count) = (r.u8()?, r.u8()?, r.u16()?);
+    let mut mines = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let (x, y) = (r.u8()?, r.u8()?);
+        if x == 0 || y == 0 || x > width || y > height {
+            return Err(format!("地雷坐标越界：({x}, {y})"));
+        }
+        mines.push((y - 1) as usize * width as usize + (x - 1) as usize);
+    }
+
+    let mut events = Vec::new();
+    let mut last = 0u8;
+    while !r.is_end() {
+        let (ms, now, x, y) = (r.u24()?, r.u8()?, r.u16()?, r.u16()?);
+        let before = events.len();
+        for (bit, b) in BUTTONS {
+            let mouse = match (last & bit != 0, now & bit != 0) {
+                (false, true) => Mouse::Press(b),
+                (true, false) => Mouse::Release(b),
+                _ => continue,
+            };
+            events.push(MouseEvent { ms, mouse, x, y });
+        }
+        if events.len() == before {
+            events.push(MouseEvent {
+                ms,
+                mouse: Mouse::Move,
+                x,
+                y,
+            });
+        }
+        last = now;
+    }
+    super::build(width, height, &mines, &events)
+}
@@ -0,0 +1,54 @@
+//! # Viennasaurus 录像（.rmv）
+//! 支持的结构（多字节整数为大端序）：
+//! - `magic: [u8; 4]` 固定为`*rmv`
+//! - `version: u16`
+//! - `info_len: u16`，其后`info_len`字节的玩家信息等整段跳过
+//! - `width: u8`, `height: u8`, `mines: u16`
+//! - `mines` 个 `(x: u8, y: u8)`，从0开始
+//! - 事件：`kind: u8`，0 结束；1~7 为鼠标事件，其后为`ms: u24, x: u16, y: u16`
+//!   - `kind` 1 移动，2 左键按下，3 左键松开，4 右键按下，5 右键松开，6 中键按下，7 中键松开
+
+use super::{Button, Mouse, MouseEvent, Reader, Replay};
+
+pub const MAGIC: &[u8; 4] = b"*rmv";
+
+pub fn parse(data: &[u8]) -> Result<Replay, String> {
+    let mut r = Reader::new(data);
+    if r.take(4)? != MAGIC {
+        return Err("不是RMV录像！".to_string());
+    }
+    let version = r.u16()?;
+    if version != 1 {
+        return Err(format!("不支持的RMV版本：{version}"));
+    }
+    let info = r.u16()?;
+    r.take(info as usize)?;
+    let (width, height, count) = (r.u8()?, r.u8()?, r.u16()?);
+    let mut mines = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let (x, y) = (r.u8()?, r.u8()?);
+        if x >= width || y >= height {
+            return Err(format!("地雷坐标越界：({x}, {y})"));
+        }
+        mines.push(y as usize * width as usize + x as usize);
+    }
+
+    let mut events = Vec::new();
+    loop {
+        let kind = r.u8()?;
+        let mouse = match kind {
+            0 => break,
+            1 => Mouse::Move,
+            2 => Mouse::Press(Button::Left),
+            3 => Mouse::Release(Button::Left),
+            4 => Mouse::Press(Button::Right),
+            5 => Mouse::Release(Button::Right),
+            6 => Mouse::Press(Button::Middle),
+            7 => Mouse::Release(Button::Middle),
+            k => return Err(format!("未知的RMV事件：{k}")),
+        };
+        let (ms, x, y) = (r.u24()?, r.u16()?, r.u16()?);
+        events.push(MouseEvent { ms, mouse, x, y });
+    }
+    super::build(width, height, &mines, &events)
+}
@@ -0,0 +1,143 @@
+//! # 录像校验
+//! 用于排行榜防作弊，依次检查：
+//! - 布局由声明的种子与保护策略生成，安全区由首次打开的位置决定
+//! - 时间戳单调不减，相邻操作的间隔不小于下限，任意时间窗口内的操作数不超过上限
+//! - 每步操作都在棋盘内、发生在对局结束之前
+//!   - 无效操作（如重复打开）在AVF、RMV等录像中很常见，不算作弊，只计数
+//! - 对局最终获胜
+
+use super::Replay;
+use crate::{
+    game::{Action, Game, Status},
+    mmap::MineMap,
+};
+
+/// 人类操作的合理范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// 相邻两次操作的最小间隔（毫秒）
+    pub min_gap_ms: u32,
+    /// 统计连续操作的时间窗口（毫秒）
+    pub window_ms: u32,
+    /// 时间窗口内的最大操作数
+    pub max_in_window: usize,
+    /// 是否要求录像带有种子
+    pub require_seed: bool,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            min_gap_ms: 20,
+            window_ms: 1000,
+            max_in_window: 25,
+            require_seed: true,
+        }
+    }
+}
+
+/// 校验通过的成绩
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Report {
+    /// 最后一步操作距开局的毫秒数
+    pub ms: u32,
+    /// 有效操作数
+    pub actions: usize,
+    /// 无效操作数，不影响局面
+    pub ignored: usize,
+    pub bbbv: usize,
+    pub bbbv_per_sec: f64,
+}
+
+/// 解析录像文件并校验，格式见`Replay::load`
+pub fn verify_file(data: &[u8], limits: &Limits) -> Result<Report, String> {
+    verify(&Replay::load(data)?, limits)
+}
+
+pub fn verify(r: &Replay, limits: &Limits) -> Result<Report, String> {
+    let Some(first) = r.events.first() else {
+        return Err("录像中没有操作！".to_string());
+    };
+    match r.seed {
+        Some(seed) => {
+            let Action::Reveal(loc) = first.action else {
+                return Err("首步操作必须是打开！".to_string());
+            };
+            let mut map = MineMap::new(r.map.count, r.map.width, r.map.height)?;
+            map.new_game_with(Some(loc), r.safety, Some(seed));
+            if !map
+                .iter()
+                .zip(r.map.iter())
+                .all(|(a, b)| a.is_mine() == b.is_mine())
+            {
+                return Err(format!("布局不是由种子{seed}生成的！"));
+            }
+        }
+        None if limits.require_seed => return Err("录像缺少种子！".to_string()),
+        None => {}
+    }
+
+    let ms: Vec<u32> = r.events.iter().map(|e| e.ms).collect();
+    let mut start = 0;
+    for (i, w) in ms.windows(2).enumerate() {
+        let (step, gap) = (i + 2, w[1].wrapping_sub(w[0]));
+        if w[1] < w[0] {
+            return Err(format!(
+                "第{step}步的时间早于上一步：{}ms < {}ms",
+                w[1], w[0]
+            ));
+        }
+        if gap < limits.min_gap_ms {
+            return Err(format!(
+                "第{step}步与上一步仅间隔{gap}ms，小于{}ms",
+                limits.min_gap_ms
+            ));
+        }
+        while ms[i + 1] - ms[start] >= limits.window_ms {
+            start += 1;
+        }
+        if i + 2 - start > limits.max_in_window {
+            return Err(format!(
+                "第{step}步前{}ms内有{}次操作，超过{}次",
+                limits.window_ms,
+                i + 2 - start,
+                limits.max_in_window
+            ));
+        }
+    }
+
+    let mut game = Game::by_map(r.map.clone());
+    let (w, h) = (r.map.width, r.map.height);
+    let mut ignored = 0;
+    for (i, e) in r.events.iter().enumerate() {
+        let loc = e.action.loc();
+        if loc.0 >= w || loc.1 >= h {
+            return Err(format!("第{}步坐标越界：{loc}", i + 1));
+        }
+        if game.is_over() {
+            return Err(format!("第{}步发生在对局结束之后", i + 1));
+        }
+        let before = game.actions();
+        game.act(e.action);
+        if game.actions() == before {
+            ignored += 1;
+        }
+    }
+    if game.status() != Status::Won {
+        return Err("对局没有获胜！".to_string());
+    }
+
+    let last = *ms.last().unwrap_or(&0);
+    let bbbv = r.map.count_3bv();
+    Ok(Report {
+        ms: last,
+        actions: r.events.len() - ignored,
+        ignored,
+        bbbv,
+        bbbv_per_sec: if last > 0 {
+            bbbv as f64 * 1000.0 / last as f64
+        } else {
+            0.0
+        },
+    })
+}
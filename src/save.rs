@@ -0,0 +1,261 @@
+//! # 存档格式
+//! 小端序，依次为：
+//! - `magic: [u8; 4]` 固定为`MSAV`
+//! - `version: u8` 写入时的格式版本
+//! - `header_len: u16` 其后头部字段的字节数
+//! - 头部字段（版本1，共31字节）
+//!   - `kind: u8` 0 仅布局，1 含进度
+//!   - `width: u8`, `height: u8`, `count: u16`
+//!   - `status: u8` 见`Status`，依次为0~3
+//!   - `boom: [u8; 2]` 踩中的地雷坐标，没有时为`[255, 255]`
+//!   - `has_seed: u8`, `seed: u64`
+//!   - `elapsed: u32` 用时（毫秒）
+//!   - `actions: u32` 有效操作数
+//!   - `safety: [u8; 2]` 首次打开的保护策略，见`Safety`
+//!   - `events: u32` 操作记录条数，仅布局时为0
+//! - `cells: [u8; width * height]` 单元格数据，见`Cell`
+//! - 操作记录，每条7字节：`ms: u32, kind: u8, x: u8, y: u8`
+//!   - `kind` 0 打开，1 插旗，2 双击
+//! - `crc: u32` 之前全部字节的CRC32
+//!
+//! 读取时跳过不认识的头部字段，因此新版本只能在头部末尾追加字段。
+
+use std::time::{Duration, Instant};
+
+use crate::{
+    game::{Action, Game, Status},
+    location::Loc,
+    mmap::{MineMap, Safety},
+    replay::Event,
+};
+
+pub const MAGIC: &[u8; 4] = b"MSAV";
+pub const VERSION: u8 = 1;
+/// 当前版本的头部字段长度
+const HEADER: usize = 31;
+/// 每条操作记录的字节数
+const EVENT_LEN: usize = 7;
+/// `magic`、`version`、`header_len`
+const PREFIX: usize = 7;
+
+/// 存档内容
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// 仅布局，不含进度
+    Layout = 0,
+    /// 含进度、计时等对局信息
+    Progress = 1,
+}
+
+/// CRC32（IEEE）
+pub fn crc32(data: &[u8]) -> u32 {
+    const TABLE: [u32; 256] = {
+        let mut t = [0u32; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut c = i as u32;
+            let mut k = 0;
+            while k < 8 {
+                c = if c & 1 != 0 {
+                    0xedb8_8320 ^ (c >> 1)
+                } else {
+                    c >> 1
+                };
+                k += 1;
+            }
+            t[i] = c;
+            i += 1;
+        }
+        t
+    };
+    !data.iter().fold(!0u32, |c, &b| {
+        TABLE[((c ^ b as u32) & 0xff) as usize] ^ (c >> 8)
+    })
+}
+
+fn status_code(s: Status) -> u8 {
+    match s {
+        Status::Ready => 0,
+        Status::Playing => 1,
+        Status::Won => 2,
+        Status::Lost => 3,
+    }
+}
+
+fn write(game: &Game, kind: Kind) -> Vec<u8> {
+    let map = game.map();
+    let progress = kind == Kind::Progress;
+    let history = if progress { game.history() } else { &[] };
+    let mut buf =
+        Vec::with_capacity(PREFIX + HEADER + map.map.len() + history.len() * EVENT_LEN + 4);
+    buf.extend(MAGIC);
+    buf.push(VERSION);
+    buf.extend((HEADER as u16).to_le_bytes());
+    buf.push(kind as u8);
+    buf.extend([map.width, map.height]);
+    buf.extend(map.count.to_le_bytes());
+    buf.push(if progress {
+        status_code(game.status())
+    } else {
+        0
+    });
+    match game.boom().filter(|_| progress) {
+        Some(Loc(x, y)) => buf.extend([x, y]),
+        None => buf.extend([u8::MAX, u8::MAX]),
+    }
+    buf.push(game.seed().is_some() as u8);
+    buf.extend(game.seed().unwrap_or(0).to_le_bytes());
+    let (elapsed, actions) = if progress {
+        let ms = game.elapsed().as_millis().min(u32::MAX as u128) as u32;
+        (ms, game.actions())
+    } else {
+        (0, 0)
+    };
+    buf.extend(elapsed.to_le_bytes());
+    buf.extend(actions.to_le_bytes());
+    buf.extend(game.safety().encode());
+    buf.extend((history.len() as u32).to_le_bytes());
+    buf.extend(&map.export(progress)[2..]);
+    for e in history {
+        let kind = match e.action {
+            Action::Reveal(_) => 0,
+            Action::Flag(_) => 1,
+            Action::Chord(_) => 2,
+        };
+        let Loc(x, y) = e.action.loc();
+        buf.extend(e.ms.to_le_bytes());
+        buf.extend([kind, x, y]);
+    }
+    let crc = crc32(&buf);
+    buf.extend(crc.to_le_bytes());
+    buf
+}
+
+/// 仅保存布局
+/// - 尚未布置地雷时失败
+pub fn export_layout(map: &MineMap) -> Result<Vec<u8>, String> {
+    if map.iter().filter(|c| c.is_mine()).count() != map.count as usize {
+        return Err("尚未布置地雷，无法导出！".to_string());
+    }
+    // 借用对局的写入逻辑，布局存档不含对局信息
+    Ok(write(&Game::by_map(map.clone()), Kind::Layout))
+}
+
+/// 保存对局进度
+pub fn export_game(game: &Game) -> Vec<u8> {
+    write(game, Kind::Progress)
+}
+
+/// # 读取存档
+/// - 不以`MSAV`开头的数据视为旧格式`[宽width, 高height, 数据data..]`，见`migrate`
+/// # Returns
+/// - `(存档内容, 对局)`；仅布局的存档得到进行中的对局
+pub fn import(data: &[u8]) -> Result<(Kind, Game), String> {
+    if !data.starts_with(MAGIC) {
+        return import_legacy(data);
+    }
+    if data.len() < PREFIX + HEADER + 4 {
+        return Err("存档数据太短！".to_string());
+    }
+    let (body, crc) = data.split_at(data.len() - 4);
+    if crc32(body) != u32::from_le_bytes([crc[0], crc[1], crc[2], crc[3]]) {
+        return Err("存档校验失败！".to_string());
+    }
+    // 更新的版本只在头部末尾追加字段，按头部长度跳过即可
+    if body[4] == 0 {
+        return Err("不支持的存档版本：0".to_string());
+    }
+    let header_len = u16::from_le_bytes([body[5], body[6]]) as usize;
+    if header_len < HEADER || body.len() < PREFIX + header_len {
+        return Err("存档头部不完整！".to_string());
+    }
+    let h = &body[PREFIX..PREFIX + header_len];
+    let kind = match h[0] {
+        0 => Kind::Layout,
+        1 => Kind::Progress,
+        k => return Err(format!("未知的存档类型：{k}")),
+    };
+    let (width, height) = (h[1], h[2]);
+    let count = u16::from_le_bytes([h[3], h[4]]);
+    let status = match h[5] {
+        0 => Status::Ready,
+        1 => Status::Playing,
+        2 => Status::Won,
+        3 => Status::Lost,
+        s => return Err(format!("未知的对局状态：{s}")),
+    };
+    let boom = (h[6] != u8::MAX).then_some(Loc(h[6], h[7]));
+    let seed = (h[8] != 0).then(|| u64::from_le_bytes(h[9..17].try_into().unwrap()));
+    let elapsed = u32::from_le_bytes(h[17..21].try_into().unwrap());
+    let actions = u32::from_le_bytes(h[21..25].try_into().unwrap());
+    let safety = Safety::decode([h[25], h[26]])?;
+    let events = u32::from_le_bytes(h[27..31].try_into().unwrap()) as usize;
+
+    let size = width as usize * height as usize;
+    let rest = &body[PREFIX + header_len..];
+    if events
+        .checked_mul(EVENT_LEN)
+        .and_then(|n| n.checked_add(size))
+        != Some(rest.len())
+    {
+        return Err("单元格或操作记录的长度与头部不符！".to_string());
+    }
+    let (cells, rest) = rest.split_at(size);
+    let mut history = Vec::with_capacity(events);
+    for e in rest.chunks_exact(EVENT_LEN) {
+        let loc = Loc(e[5], e[6]);
+        let action = match e[4] {
+            0 => Action::Reveal(loc),
+            1 => Action::Flag(loc),
+            2 => Action::Chord(loc),
+            k => return Err(format!("未知的操作：{k}")),
+        };
+        history.push(Event {
+            ms: u32::from_le_bytes(e[..4].try_into().unwrap()),
+            action,
+        });
+    }
+    if kind == Kind::Progress && status == Status::Ready {
+        // 尚未开局，只需恢复尺寸、种子与保护策略
+        let map = MineMap::new(count, width, height)?;
+        let mut game = Game::new(map).with_safety(safety);
+        game.seed = seed;
+        return Ok((kind, game));
+    }
+    let mut raw = Vec::with_capacity(cells.len() + 2);
+    raw.extend([width, height]);
+    raw.extend(cells);
+    let map = MineMap::by(raw, kind == Kind::Progress)?;
+    if map.count != count {
+        return Err(format!("地雷数不符：头部{count}，实际{}", map.count));
+    }
+    let mut game = Game::by_map(map).with_safety(safety);
+    game.seed = seed;
+    if kind == Kind::Progress {
+        game.history = history;
+        game.status = status;
+        game.boom = boom;
+        game.actions = actions;
+        game.elapsed = Duration::from_millis(elapsed as u64);
+        game.since = (status == Status::Playing).then(Instant::now);
+    }
+    Ok((kind, game))
+}
+
+/// 读取旧格式`[宽width, 高height, 数据data..]`
+/// - 含有打开、插旗标记时视为含进度的存档
+fn import_legacy(data: &[u8]) -> Result<(Kind, Game), String> {
+    let map = MineMap::by(data.to_vec(), true)?;
+    let kind = if map.map.iter().any(|&v| v & 0xc0 != 0) {
+        Kind::Progress
+    } else {
+        Kind::Layout
+    };
+    Ok((kind, Game::by_map(map)))
+}
+
+/// 将旧格式转换为当前格式
+pub fn migrate(legacy: &[u8]) -> Result<Vec<u8>, String> {
+    let (kind, game) = import_legacy(legacy)?;
+    Ok(write(&game, kind))
+}
@@ -0,0 +1,120 @@
+use crate::{location::Loc, mmap::MineMap, save::crc32};
+
+/// 分享码格式版本
+const CODE_VERSION: u8 = 1;
+/// URL安全的base64字符表
+const B64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// 按位打包布局，每个单元格1位，低位在前
+/// # Returns
+/// - `[宽width, 高height, 位图bits..]`
+/// - 尚未布置地雷时失败
+pub fn pack(map: &MineMap) -> Result<Vec<u8>, String> {
+    if map.iter().filter(|c| c.is_mine()).count() != map.count as usize {
+        return Err("尚未布置地雷，无法导出！".to_string());
+    }
+    let mut res = vec![0u8; 2 + map.map.len().div_ceil(8)];
+    res[0] = map.width;
+    res[1] = map.height;
+    for (i, c) in map.iter().enumerate() {
+        if c.is_mine() {
+            res[2 + i / 8] |= 1 << (i % 8);
+        }
+    }
+    Ok(res)
+}
+
+/// 导入按位打包的布局，警示数值重新计算
+/// - 数据格式见`pack`
+pub fn unpack(data: &[u8]) -> Result<MineMap, String> {
+    let [w, h, bits @ ..] = data else {
+        return Err("输入数据太短！[宽, 高, 位图..]".to_string());
+    };
+    let size = *w as usize * *h as usize;
+    if bits.len() != size.div_ceil(8) {
+        return Err("位图长度与宽高不符！".to_string());
+    }
+    let mines: Vec<usize> = (0..size)
+        .filter(|i| bits[i / 8] >> (i % 8) & 1 == 1)
+        .collect();
+    MineMap::by_mines(*w, *h, &mines)
+}
+
+/// # 生成分享码
+/// URL安全、无填充的base64，内容为：
+/// - `version: u8`
+/// - `first: [u8; 2]` 首次打开的坐标，没有时为`[255, 255]`
+/// - `layout` 见`pack`
+/// - `check: [u8; 2]` 之前全部字节CRC32的低16位
+pub fn encode_code(map: &MineMap, first: Option<Loc>) -> Result<String, String> {
+    let mut raw = vec![CODE_VERSION];
+    match first {
+        Some(Loc(x, y)) => raw.extend([x, y]),
+        None => raw.extend([u8::MAX, u8::MAX]),
+    }
+    raw.extend(pack(map)?);
+    let check = crc32(&raw) as u16;
+    raw.extend(check.to_le_bytes());
+    Ok(to_base64(&raw))
+}
+
+/// 解析分享码
+/// # Returns
+/// - `(布局, 首次打开的坐标)`
+pub fn decode_code(code: &str) -> Result<(MineMap, Option<Loc>), String> {
+    let raw = from_base64(code.trim())?;
+    if raw.len() < 8 {
+        return Err("分享码太短！".to_string());
+    }
+    let (body, check) = raw.split_at(raw.len() - 2);
+    if crc32(body) as u16 != u16::from_le_bytes([check[0], check[1]]) {
+        return Err("分享码校验失败！".to_string());
+    }
+    if body[0] != CODE_VERSION {
+        return Err(format!("不支持的分享码版本：{}", body[0]));
+    }
+    let first = (body[1] != u8::MAX).then_some(Loc(body[1], body[2]));
+    let map = unpack(&body[3..])?;
+    if let Some(Loc(x, y)) = first {
+        if x >= map.width || y >= map.height {
+            return Err(format!("首次打开的坐标越界：{}", Loc(x, y)));
+        }
+    }
+    Ok((map, first))
+}
+
+fn to_base64(data: &[u8]) -> String {
+    let mut res = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        // 每3字节输出4个字符，不足时省略末尾字符
+        for i in 0..=chunk.len() {
+            res.push(B64[(n >> (18 - 6 * i) & 63) as usize] as char);
+        }
+    }
+    res
+}
+
+fn from_base64(s: &str) -> Result<Vec<u8>, String> {
+    let mut res = Vec::with_capacity(s.len() * 3 / 4);
+    let (mut acc, mut bits) = (0u32, 0);
+    for ch in s.bytes() {
+        let Some(v) = B64.iter().position(|&b| b == ch) else {
+            return Err(format!("分享码含有无效字符：{}", ch as char));
+        };
+        acc = acc << 6 | v as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            res.push((acc >> bits) as u8);
+        }
+    }
+    // 末尾不足一个字节的位必须为0，且不能多出单个字符
+    if bits >= 6 || acc & ((1 << bits) - 1) != 0 {
+        return Err("分享码长度或末尾字符无效！".to_string());
+    }
+    Ok(res)
+}
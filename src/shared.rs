@@ -0,0 +1,303 @@
+//! # 多人共享棋盘
+//! 多名玩家在同一个`MineMap`上操作：
+//! - 合作：任何人随时可以操作，踩雷则全体失败
+//! - 对战：轮流操作，打开或双击后轮到下一名玩家，插旗不换手；
+//!   打开安全单元格得分，踩雷扣分但对局继续，安全单元格全部打开后结束
+//!
+//! 每个被打开的单元格都记录打开者。需要在多线程间共享时使用`Table`。
+
+use std::sync::{Mutex, MutexGuard};
+
+use crate::{
+    game::{Action, Status},
+    location::Loc,
+    mmap::{MineMap, Safety},
+};
+
+/// 计分规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Scoring {
+    /// 每打开一个安全单元格
+    pub safe: i32,
+    /// 每踩中一颗地雷，通常为负数
+    pub mine: i32,
+}
+
+impl Default for Scoring {
+    fn default() -> Self {
+        Self { safe: 1, mine: -10 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// 合作，得分为各自打开的安全单元格数
+    Coop,
+    /// 轮流对战
+    Versus(Scoring),
+}
+
+/// 一次操作的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Outcome {
+    /// 打开的安全单元格数
+    pub opened: usize,
+    /// 踩中的地雷数
+    pub mines: usize,
+    /// 本次得分
+    pub score: i32,
+    pub status: Status,
+    /// 对战中下一个操作的玩家
+    pub turn: Option<u8>,
+}
+
+/// # 共享对局
+pub struct Shared {
+    map: MineMap,
+    seed: Option<u64>,
+    /// 首次打开的保护策略
+    safety: Safety,
+    mode: Mode,
+    status: Status,
+    players: u8,
+    turn: u8,
+    /// 每个单元格的打开者
+    owners: Vec<Option<u8>>,
+    scores: Vec<i32>,
+    /// 有效操作及操作者
+    history: Vec<(u8, Action)>,
+}
+
+impl Shared {
+    /// 新对局，首次打开时随机布置地雷
+    pub fn new(map: MineMap, mode: Mode, players: u8) -> Result<Self, String> {
+        let min = if matches!(mode, Mode::Versus(_)) {
+            2
+        } else {
+            1
+        };
+        if players < min {
+            return Err(format!("玩家数至少为{min}！"));
+        }
+        Ok(Self {
+            owners: vec![None; map.map.len()],
+            map,
+            seed: None,
+            safety: Safety::default(),
+            mode,
+            status: Status::Ready,
+            players,
+            turn: 0,
+            scores: vec![0; players as usize],
+            history: Vec::new(),
+        })
+    }
+
+    /// 新对局，首次打开时基于种子布置地雷
+    pub fn by_seed(map: MineMap, mode: Mode, players: u8, seed: u64) -> Result<Self, String> {
+        let mut s = Self::new(map, mode, players)?;
+        s.seed = Some(seed);
+        Ok(s)
+    }
+
+    /// 基于已布置好地雷的地图开局
+    /// - 地图中已有的进度不计入任何玩家
+    pub fn by_map(map: MineMap, mode: Mode, players: u8) -> Result<Self, String> {
+        let mut s = Self::new(map, mode, players)?;
+        s.status = Status::Playing;
+        Ok(s)
+    }
+
+    /// 设置首次打开的保护策略，开局后设置无效
+    pub fn with_safety(mut self, safety: Safety) -> Self {
+        self.safety = safety;
+        self
+    }
+
+    #[inline]
+    pub fn map(&self) -> &MineMap {
+        &self.map
+    }
+
+    #[inline]
+    pub fn safety(&self) -> Safety {
+        self.safety
+    }
+
+    #[inline]
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    #[inline]
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    #[inline]
+    pub fn is_over(&self) -> bool {
+        matches!(self.status, Status::Won | Status::Lost)
+    }
+
+    #[inline]
+    pub fn players(&self) -> u8 {
+        self.players
+    }
+
+    /// 对战中轮到的玩家，合作或已结束时为`None`
+    pub fn turn(&self) -> Option<u8> {
+        (matches!(self.mode, Mode::Versus(_)) && !self.is_over()).then_some(self.turn)
+    }
+
+    #[inline]
+    pub fn scores(&self) -> &[i32] {
+        &self.scores
+    }
+
+    /// 每个单元格的打开者，与`map.map`等长
+    #[inline]
+    pub fn owners(&self) -> &[Option<u8>] {
+        &self.owners
+    }
+
+    pub fn owner(&self, Loc(x, y): Loc) -> Option<u8> {
+        let (w, h) = (self.map.width, self.map.height);
+        if x >= w || y >= h {
+            return None;
+        }
+        self.owners[y as usize * w as usize + x as usize]
+    }
+
+    #[inline]
+    pub fn history(&self) -> &[(u8, Action)] {
+        &self.history
+    }
+
+    /// 得分最高的玩家，可能并列
+    pub fn leaders(&self) -> Vec<u8> {
+        let Some(&best) = self.scores.iter().max() else {
+            return Vec::new();
+        };
+        (0..self.players)
+            .filter(|&p| self.scores[p as usize] == best)
+            .collect()
+    }
+
+    /// # 执行操作
+    /// 无效操作（如打开已打开的单元格）不换手，返回的结果中打开数为0
+    /// # Errors
+    /// - 对局已结束、玩家不存在、未轮到该玩家、坐标越界
+    pub fn act(&mut self, player: u8, action: Action) -> Result<Outcome, String> {
+        if self.is_over() {
+            return Err("对局已结束！".to_string());
+        }
+        if player >= self.players {
+            return Err(format!("玩家{player}不存在！"));
+        }
+        if let Some(turn) = self.turn() {
+            if turn != player {
+                return Err(format!("尚未轮到玩家{player}，当前为玩家{turn}"));
+            }
+        }
+        let loc = action.loc();
+        let Some(c) = self.map.get_by_loc(loc) else {
+            return Err(format!("坐标越界：{loc}"));
+        };
+        let (x, y) = (loc.0 as usize, loc.1 as usize);
+        let before: Vec<bool> = self.map.iter().map(|c| c.is_reveal()).collect();
+        let mut flagged = false;
+        match action {
+            Action::Reveal(_) => {
+                if self.status == Status::Ready {
+                    self.map.new_game_with(Some(loc), self.safety, self.seed);
+                    self.status = Status::Playing;
+                } else if c.is_reveal() || c.is_flagged() {
+                    return Ok(self.outcome(0, 0, 0));
+                }
+                self.map.reveal(x, y);
+            }
+            Action::Flag(_) => {
+                if self.status == Status::Playing && !c.is_reveal() {
+                    self.map.switch_flag(x, y);
+                    flagged = true;
+                }
+            }
+            Action::Chord(_) => {
+                if c.is_reveal()
+                    && !c.is_empty()
+                    && self.map.count_flagged_around(x, y) == c.get_warn() as usize
+                {
+                    self.map.reveal_around(x, y);
+                }
+            }
+        }
+
+        let (mut opened, mut mines) = (0, 0);
+        for (i, c) in self.map.iter().enumerate() {
+            if before[i] || !c.is_reveal() {
+                continue;
+            }
+            self.owners[i] = Some(player);
+            if c.is_mine() {
+                mines += 1;
+            } else {
+                opened += 1;
+            }
+        }
+        if opened + mines == 0 && !flagged {
+            return Ok(self.outcome(0, 0, 0));
+        }
+        self.history.push((player, action));
+        let scoring = match self.mode {
+            Mode::Coop => Scoring { safe: 1, mine: 0 },
+            Mode::Versus(s) => s,
+        };
+        let score = opened as i32 * scoring.safe + mines as i32 * scoring.mine;
+        self.scores[player as usize] += score;
+
+        if mines > 0 && self.mode == Mode::Coop {
+            self.status = Status::Lost;
+            self.map.reveal_all_mines();
+        } else if self.map.is_all_reveal() {
+            self.status = Status::Won;
+        } else if opened + mines > 0 {
+            self.turn = (self.turn + 1) % self.players;
+        }
+        Ok(self.outcome(opened, mines, score))
+    }
+
+    fn outcome(&self, opened: usize, mines: usize, score: i32) -> Outcome {
+        Outcome {
+            opened,
+            mines,
+            score,
+            status: self.status,
+            turn: self.turn(),
+        }
+    }
+}
+
+/// # 可在多线程间共享的对局
+/// 操作按获得锁的先后依次执行，基于旧局面的操作会成为无效操作或返回错误，
+/// 不会破坏局面。
+pub struct Table(Mutex<Shared>);
+
+impl Table {
+    pub fn new(shared: Shared) -> Self {
+        Self(Mutex::new(shared))
+    }
+
+    pub fn act(&self, player: u8, action: Action) -> Result<Outcome, String> {
+        self.lock().act(player, action)
+    }
+
+    /// 读取当前局面
+    pub fn lock(&self) -> MutexGuard<'_, Shared> {
+        // 操作中不会panic，锁中毒时局面仍然完整
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn into_inner(self) -> Shared {
+        self.0.into_inner().unwrap_or_else(|e| e.into_inner())
+    }
+}
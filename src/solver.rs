@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+
+use crate::{cell::Cell, mmap::around_index};
+
+/// 单个连通块枚举的节点上限，超过后放弃计算
+const MAX_NODES: usize = 1 << 22;
+
+/// 一个数字对周围未知单元格的约束
+struct Rule {
+    /// 未知单元格在前沿中的序号
+    cells: Vec<usize>,
+    /// 剩余地雷数
+    mines: u8,
+}
+
+/// 一个连通块按地雷数汇总的解
+struct Block {
+    /// 块内前沿单元格序号
+    cells: Vec<usize>,
+    /// `ways[k]` 恰有k颗雷的解的数量
+    ways: Vec<f64>,
+    /// `hits[k][j]` 恰有k颗雷的解中第j个单元格为雷的数量
+    hits: Vec<Vec<f64>>,
+}
+
+/// # 计算每个单元格为地雷的精确概率
+/// 枚举前沿（与数字相邻的未知单元格）的全部合法布局，
+/// 再按剩余地雷在非前沿单元格中的组合数加权汇总。
+/// - 插旗的单元格视为未知，不作为推理依据
+/// - 已打开的地雷视为已知地雷
+/// # Argument
+/// - `view` 玩家视角的数据，见`MineMap::view`
+/// # Returns
+/// - 与`view`等长的概率，已打开的非雷单元格为0
+/// - 局面矛盾、枚举规模过大或区域小于2x2时返回`None`
+pub fn probabilities(width: u8, height: u8, count: u16, view: &[u8]) -> Option<Vec<f64>> {
+    let (w, h) = (width as usize, height as usize);
+    let size = w * h;
+    if view.len() != size || w < 2 || h < 2 {
+        return None;
+    }
+    let hidden = |i: usize| !Cell(view[i]).is_reveal();
+    let known = |i: usize| {
+        let c = Cell(view[i]);
+        c.is_reveal() && c.is_mine()
+    };
+
+    // 收集约束，同时为前沿单元格编号
+    let mut front: Vec<usize> = Vec::new();
+    let mut front_idx: HashMap<usize, usize> = HashMap::new();
+    let mut rules = Vec::new();
+    for (i, &v) in view.iter().enumerate() {
+        let c = Cell(v);
+        if !c.is_reveal() || c.is_mine() {
+            continue;
+        }
+        let mut mines = c.get_warn() as i32;
+        let mut cells = Vec::new();
+        for a in around_index(i, w, h) {
+            if known(a) {
+                mines -= 1;
+            } else if hidden(a) {
+                let n = front.len();
+                let j = *front_idx.entry(a).or_insert_with(|| {
+                    front.push(a);
+                    n
+                });
+                cells.push(j);
+            }
+        }
+        if mines < 0 || mines as usize > cells.len() {
+            return None;
+        }
+        if !cells.is_empty() {
+            rules.push(Rule {
+                cells,
+                mines: mines as u8,
+            });
+        }
+    }
+
+    let known_mines = (0..size).filter(|&i| known(i)).count();
+    let rest = (count as usize).checked_sub(known_mines)?;
+    let interior = (0..size)
+        .filter(|&i| hidden(i) && !front_idx.contains_key(&i))
+        .count();
+
+    let blocks = split_blocks(front.len(), &rules)
+        .into_iter()
+        .map(|(cells, rs)| enumerate(cells, &rs, &rules))
+        .collect::<Option<Vec<_>>>()?;
+
+    // 非前沿放置r颗雷的组合数，取对数以免溢出
+    let ln_comb = |r: usize| -> Option<f64> {
+        if r > interior {
+            return None;
+        }
+        Some(
+            (1..=r)
+                .map(|j| ((interior - r + j) as f64 / j as f64).ln())
+                .sum(),
+        )
+    };
+    // 前沿总雷数为m时的权重，按最大值归一化
+    let max_front = front.len().min(rest);
+    let ln_w: Vec<Option<f64>> = (0..=max_front).map(|m| ln_comb(rest - m)).collect();
+    let top = ln_w
+        .iter()
+        .flatten()
+        .cloned()
+        .fold(f64::NEG_INFINITY, f64::max);
+    if top == f64::NEG_INFINITY {
+        return None;
+    }
+    let weight: Vec<f64> = ln_w
+        .iter()
+        .map(|v| v.map_or(0.0, |v| (v - top).exp()))
+        .collect();
+
+    // 所有连通块组合后的总雷数分布
+    let total = blocks
+        .iter()
+        .fold(vec![1.0], |acc, b| convolve(&acc, &b.ways));
+    let sum: f64 = total
+        .iter()
+        .enumerate()
+        .map(|(m, &v)| v * weight.get(m).copied().unwrap_or(0.0))
+        .sum();
+    if sum <= 0.0 {
+        return None;
+    }
+
+    let mut res = vec![0.0; size];
+    for (bi, b) in blocks.iter().enumerate() {
+        // 除当前块外其余块的雷数分布
+        let others = blocks
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| j != bi)
+            .fold(vec![1.0], |acc, (_, o)| convolve(&acc, &o.ways));
+        for (k, hits) in b.hits.iter().enumerate() {
+            let f: f64 = others
+                .iter()
+                .enumerate()
+                .map(|(m, &v)| v * weight.get(k + m).copied().unwrap_or(0.0))
+                .sum();
+            for (j, &hit) in hits.iter().enumerate() {
+                res[front[b.cells[j]]] += hit * f / sum;
+            }
+        }
+    }
+    if interior > 0 {
+        let p: f64 = total
+            .iter()
+            .enumerate()
+            .filter(|&(m, _)| m <= rest)
+            .map(|(m, &v)| {
+                v * weight.get(m).copied().unwrap_or(0.0) * (rest - m) as f64 / interior as f64
+            })
+            .sum::<f64>()
+            / sum;
+        for (i, r) in res.iter_mut().enumerate() {
+            if hidden(i) && !front_idx.contains_key(&i) {
+                *r = p;
+            }
+        }
+    }
+    for (i, r) in res.iter_mut().enumerate() {
+        if known(i) {
+            *r = 1.0;
+        }
+    }
+    Some(res)
+}
+
+/// 按共享约束把前沿拆分为互不相关的连通块
+/// # Returns
+/// - `[(块内前沿序号, 块内约束序号)]`
+fn split_blocks(n: usize, rules: &[Rule]) -> Vec<(Vec<usize>, Vec<usize>)> {
+    let mut parent: Vec<usize> = (0..n).collect();
+    fn find(p: &mut [usize], mut i: usize) -> usize {
+        while p[i] != i {
+            p[i] = p[p[i]];
+            i = p[i];
+        }
+        i
+    }
+    for r in rules {
+        let a = find(&mut parent, r.cells[0]);
+        for &c in &r.cells[1..] {
+            let b = find(&mut parent, c);
+            parent[b] = a;
+        }
+    }
+    let mut groups: HashMap<usize, (Vec<usize>, Vec<usize>)> = HashMap::new();
+    for i in 0..n {
+        let root = find(&mut parent, i);
+        groups.entry(root).or_default().0.push(i);
+    }
+    for (ri, r) in rules.iter().enumerate() {
+        let root = find(&mut parent, r.cells[0]);
+        groups.entry(root).or_default().1.push(ri);
+    }
+    let mut ls: Vec<_> = groups.into_values().collect();
+    ls.sort_by_key(|g| g.0[0]);
+    ls
+}
+
+/// 回溯枚举一个连通块的全部解
+fn enumerate(cells: Vec<usize>, rs: &[usize], rules: &[Rule]) -> Option<Block> {
+    // 块内序号
+    let local: HashMap<usize, usize> = cells.iter().enumerate().map(|(j, &c)| (c, j)).collect();
+    // 每个单元格涉及的约束
+    let mut by_cell = vec![Vec::new(); cells.len()];
+    // 约束的剩余雷数、剩余未定单元格数
+    let mut need = Vec::with_capacity(rs.len());
+    let mut free = Vec::with_capacity(rs.len());
+    for (k, &ri) in rs.iter().enumerate() {
+        for c in &rules[ri].cells {
+            by_cell[local[c]].push(k);
+        }
+        need.push(rules[ri].mines as i32);
+        free.push(rules[ri].cells.len() as i32);
+    }
+    let n = cells.len();
+    let mut b = Block {
+        cells,
+        ways: vec![0.0; n + 1],
+        hits: vec![vec![0.0; n]; n + 1],
+    };
+    let mut pick = vec![false; n];
+    let mut nodes = 0;
+
+    struct Ctx<'a> {
+        by_cell: &'a [Vec<usize>],
+        need: Vec<i32>,
+        free: Vec<i32>,
+        pick: &'a mut [bool],
+        nodes: &'a mut usize,
+        b: &'a mut Block,
+    }
+    fn dfs(ctx: &mut Ctx, j: usize, mines: usize) -> bool {
+        *ctx.nodes += 1;
+        if *ctx.nodes > MAX_NODES {
+            return false;
+        }
+        if j == ctx.pick.len() {
+            ctx.b.ways[mines] += 1.0;
+            for (k, &p) in ctx.pick.iter().enumerate() {
+                if p {
+                    ctx.b.hits[mines][k] += 1.0;
+                }
+            }
+            return true;
+        }
+        for mine in [false, true] {
+            let ok = ctx.by_cell[j].iter().all(|&k| {
+                let need = ctx.need[k] - mine as i32;
+                need >= 0 && need < ctx.free[k]
+            });
+            if !ok {
+                continue;
+            }
+            for &k in &ctx.by_cell[j] {
+                ctx.need[k] -= mine as i32;
+                ctx.free[k] -= 1;
+            }
+            ctx.pick[j] = mine;
+            let done = dfs(ctx, j + 1, mines + mine as usize);
+            for &k in &ctx.by_cell[j] {
+                ctx.need[k] += mine as i32;
+                ctx.free[k] += 1;
+            }
+            if !done {
+                return false;
+            }
+        }
+        ctx.pick[j] = false;
+        true
+    }
+    let mut ctx = Ctx {
+        by_cell: &by_cell,
+        need,
+        free,
+        pick: &mut pick,
+        nodes: &mut nodes,
+        b: &mut b,
+    };
+    if !dfs(&mut ctx, 0, 0) {
+        return None;
+    }
+    Some(b)
+}
+
+/// 两个雷数分布的卷积
+fn convolve(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut res = vec![0.0; a.len() + b.len() - 1];
+    for (i, &x) in a.iter().enumerate() {
+        if x == 0.0 {
+            continue;
+        }
+        for (j, &y) in b.iter().enumerate() {
+            res[i + j] += x * y;
+        }
+    }
+    res
+}
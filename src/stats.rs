@@ -0,0 +1,332 @@
+//! # 本地成绩与统计
+//! 按玩家档案与难度（见`Difficulty`）记录：局数、胜局、最佳用时、最佳3BV/s、
+//! 连胜以及完成用时的分布。
+//!
+//! 文件为UTF-8文本，首行为`mines-stats <版本>`，其后每行一条记录，字段以制表符分隔：
+//! - `profile`, `difficulty` 难度为`宽x高/雷数`，标准难度也按尺寸记录
+//! - `played`, `won`, `streak`, `best_streak`
+//! - `best_ms` 没有胜局时为`-`
+//! - `best_bbbv_per_sec` 没有胜局时为`-`
+//! - `histogram` 胜局用时（整秒）的分布，形如`12:3,15:1`，为空时为`-`
+//!
+//! 保存时先写入同目录下的临时文件再重命名，中途失败不会损坏原文件。
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    difficulty::{self, Difficulty},
+    game::{Game, Status},
+};
+
+pub const HEADER: &str = "mines-stats";
+pub const VERSION: u32 = 1;
+/// 默认文件名
+pub const FILE_NAME: &str = "stats.txt";
+
+/// 一局结束时的成绩
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Finish {
+    pub width: u8,
+    pub height: u8,
+    pub count: u16,
+    pub won: bool,
+    /// 用时（毫秒）
+    pub ms: u32,
+    pub bbbv: usize,
+}
+
+impl Finish {
+    /// 取出已结束对局的成绩，进行中返回`None`
+    pub fn of(game: &Game) -> Option<Self> {
+        if !game.is_over() {
+            return None;
+        }
+        let map = game.map();
+        Some(Self {
+            width: map.width,
+            height: map.height,
+            count: map.count,
+            won: game.status() == Status::Won,
+            ms: game.elapsed().as_millis().min(u32::MAX as u128) as u32,
+            bbbv: map.count_3bv(),
+        })
+    }
+
+    /// 与标准难度尺寸相同时为标准难度
+    pub fn difficulty(&self) -> Difficulty {
+        Difficulty::of(self.width, self.height, self.count)
+    }
+
+    pub fn bbbv_per_sec(&self) -> f64 {
+        if self.ms == 0 {
+            return 0.0;
+        }
+        self.bbbv as f64 * 1000.0 / self.ms as f64
+    }
+}
+
+/// 一个档案在一种难度下的统计
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Record {
+    pub played: u32,
+    pub won: u32,
+    /// 当前连胜
+    pub streak: u32,
+    pub best_streak: u32,
+    pub best_ms: Option<u32>,
+    pub best_bbbv_per_sec: Option<f64>,
+    /// 胜局用时（整秒） -> 局数
+    pub histogram: BTreeMap<u32, u32>,
+}
+
+impl Record {
+    /// 胜率，未玩过为0
+    pub fn win_rate(&self) -> f64 {
+        if self.played == 0 {
+            return 0.0;
+        }
+        self.won as f64 / self.played as f64
+    }
+
+    /// 按`bucket`秒分组的胜局用时分布
+    /// # Returns
+    /// - `(组起始秒数, 局数)`，按用时升序，省略空组
+    pub fn histogram(&self, bucket: u32) -> Vec<(u32, u32)> {
+        let bucket = bucket.max(1);
+        let mut out: Vec<(u32, u32)> = Vec::new();
+        for (&s, &n) in &self.histogram {
+            let k = s / bucket * bucket;
+            match out.last_mut() {
+                Some((b, m)) if *b == k => *m += n,
+                _ => out.push((k, n)),
+            }
+        }
+        out
+    }
+
+    fn add(&mut self, f: &Finish) {
+        self.played += 1;
+        if !f.won {
+            self.streak = 0;
+            return;
+        }
+        self.won += 1;
+        self.streak += 1;
+        self.best_streak = self.best_streak.max(self.streak);
+        self.best_ms = Some(self.best_ms.map_or(f.ms, |b| b.min(f.ms)));
+        let speed = f.bbbv_per_sec();
+        self.best_bbbv_per_sec = Some(self.best_bbbv_per_sec.map_or(speed, |b| b.max(speed)));
+        *self.histogram.entry(f.ms / 1000).or_default() += 1;
+    }
+}
+
+/// # 统计数据
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Store {
+    /// 档案 -> 难度 -> 统计
+    data: BTreeMap<String, BTreeMap<Difficulty, Record>>,
+}
+
+impl Store {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 用户数据目录下的默认路径：
+    /// - Linux等：`$XDG_DATA_HOME/mines`，未设置时为`~/.local/share/mines`
+    /// - macOS：`~/Library/Application Support/mines`
+    /// - Windows：`%APPDATA%\mines`
+    pub fn default_path() -> Option<PathBuf> {
+        let var = |k: &str| {
+            std::env::var_os(k)
+                .filter(|v| !v.is_empty())
+                .map(PathBuf::from)
+        };
+        let dir = if cfg!(windows) {
+            var("APPDATA")?
+        } else if cfg!(target_os = "macos") {
+            var("HOME")?.join("Library/Application Support")
+        } else {
+            var("XDG_DATA_HOME").or_else(|| Some(var("HOME")?.join(".local/share")))?
+        };
+        Some(dir.join("mines").join(FILE_NAME))
+    }
+
+    /// 读取文件，文件不存在时返回空的统计
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(s) => s.parse(),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::new()),
+            Err(e) => Err(format!("无法读取{}：{e}", path.display())),
+        }
+    }
+
+    /// 原子地写入文件，必要时创建目录
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let err = |e: std::io::Error| format!("无法写入{}：{e}", path.display());
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(err)?;
+        }
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let tmp = PathBuf::from(tmp);
+        let mut f = fs::File::create(&tmp).map_err(err)?;
+        f.write_all(self.to_string().as_bytes()).map_err(err)?;
+        f.sync_all().map_err(err)?;
+        drop(f);
+        fs::rename(&tmp, path).map_err(err)
+    }
+
+    /// 记录一局成绩，档案名中的制表符与换行会被替换为空格
+    pub fn record(&mut self, profile: &str, f: &Finish) {
+        self.data
+            .entry(clean(profile))
+            .or_default()
+            .entry(f.difficulty())
+            .or_default()
+            .add(f);
+    }
+
+    /// 记录已结束的对局
+    /// # Returns
+    /// - 对局未结束时不记录，返回`false`
+    pub fn record_game(&mut self, profile: &str, game: &Game) -> bool {
+        let Some(f) = Finish::of(game) else {
+            return false;
+        };
+        self.record(profile, &f);
+        true
+    }
+
+    /// 所有档案名，按字典序
+    pub fn profiles(&self) -> impl Iterator<Item = &str> {
+        self.data.keys().map(|s| s.as_str())
+    }
+
+    /// 档案在各难度下的统计，标准难度由易到难在前，自定义难度在后
+    pub fn difficulties(&self, profile: &str) -> impl Iterator<Item = (Difficulty, &Record)> {
+        self.data
+            .get(profile)
+            .into_iter()
+            .flatten()
+            .map(|(&d, r)| (d, r))
+    }
+
+    pub fn get(&self, profile: &str, difficulty: Difficulty) -> Option<&Record> {
+        self.data.get(profile)?.get(&difficulty)
+    }
+
+    /// 某难度下各档案的最佳用时排行
+    /// # Returns
+    /// - `(档案, 最佳用时)`，按用时升序，只含有胜局的档案
+    pub fn leaderboard(&self, difficulty: Difficulty) -> Vec<(&str, u32)> {
+        let mut out: Vec<(&str, u32)> = self
+            .data
+            .iter()
+            .filter_map(|(p, ds)| Some((p.as_str(), ds.get(&difficulty)?.best_ms?)))
+            .collect();
+        out.sort_by_key(|&(p, ms)| (ms, p));
+        out
+    }
+
+    /// 删除档案
+    pub fn remove(&mut self, profile: &str) -> bool {
+        self.data.remove(profile).is_some()
+    }
+}
+
+fn clean(s: &str) -> String {
+    s.replace(['\t', '\n', '\r'], " ")
+}
+
+impl std::fmt::Display for Store {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{HEADER} {VERSION}")?;
+        for (p, ds) in &self.data {
+            for (d, r) in ds {
+                let opt = |v: Option<String>| v.unwrap_or_else(|| "-".to_string());
+                let (w, h, c) = d.size();
+                let hist = r
+                    .histogram
+                    .iter()
+                    .map(|(s, n)| format!("{s}:{n}"))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                writeln!(
+                    f,
+                    "{p}\t{w}x{h}/{c}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                    r.played,
+                    r.won,
+                    r.streak,
+                    r.best_streak,
+                    opt(r.best_ms.map(|v| v.to_string())),
+                    opt(r.best_bbbv_per_sec.map(|v| v.to_string())),
+                    if hist.is_empty() {
+                        "-".to_string()
+                    } else {
+                        hist
+                    },
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Store {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+        let version = lines
+            .next()
+            .and_then(|l| l.strip_prefix(HEADER))
+            .and_then(|v| v.trim().parse::<u32>().ok())
+            .ok_or_else(|| "不是统计文件！".to_string())?;
+        if version > VERSION {
+            return Err(format!("不支持的统计文件版本：{version}"));
+        }
+        let mut store = Self::new();
+        for (i, line) in lines.enumerate().filter(|(_, l)| !l.is_empty()) {
+            let bad = || format!("第{}行格式错误！", i + 2);
+            let fs: Vec<&str> = line.split('\t').collect();
+            let [p, d, played, won, streak, best_streak, ms, speed, hist] = fs[..] else {
+                return Err(bad());
+            };
+            let num = |v: &str| v.parse::<u32>().map_err(|_| bad());
+            let (w, h, c) = difficulty::parse_size(d).ok_or_else(bad)?;
+            let mut r = Record {
+                played: num(played)?,
+                won: num(won)?,
+                streak: num(streak)?,
+                best_streak: num(best_streak)?,
+                best_ms: match ms {
+                    "-" => None,
+                    v => Some(num(v)?),
+                },
+                best_bbbv_per_sec: match speed {
+                    "-" => None,
+                    v => Some(v.parse().map_err(|_| bad())?),
+                },
+                histogram: BTreeMap::new(),
+            };
+            if hist != "-" {
+                for kv in hist.split(',') {
+                    let (s, n) = kv.split_once(':').ok_or_else(bad)?;
+                    r.histogram.insert(num(s)?, num(n)?);
+                }
+            }
+            store
+                .data
+                .entry(p.to_string())
+                .or_default()
+                .insert(Difficulty::of(w, h, c), r);
+        }
+        Ok(store)
+    }
+}
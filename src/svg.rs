@@ -0,0 +1,226 @@
+//! # SVG渲染
+//! 以经典扫雷的样式将地图输出为独立的SVG文件：
+//! - 未打开的单元格为凸起的方块，已打开的为平面
+//! - 数字按经典配色着色
+//! - 踩中的地雷以红色底色标出
+//! - 已踩雷或显示完整布局时，插错的旗帜画叉
+//! - 可选的坐标标注与概率热力图
+
+use std::fmt::Write;
+
+use crate::{cell::Cell, game::Game, location::Loc, mmap::MineMap};
+
+/// 数字1~8的颜色
+const WARN_COLORS: [&str; 8] = [
+    "#0000ff", "#008000", "#ff0000", "#000080", "#800000", "#008080", "#000000", "#808080",
+];
+const FACE: &str = "#c0c0c0";
+const LIGHT: &str = "#ffffff";
+const SHADOW: &str = "#808080";
+const BOOM: &str = "#ff0000";
+
+/// 渲染选项
+#[derive(Debug, Clone, Copy)]
+pub struct Options<'a> {
+    /// 单元格边长（像素）
+    pub cell: u32,
+    /// 在上方与左侧标注列号、行号
+    pub labels: bool,
+    /// 忽略进度，显示完整布局
+    pub layout: bool,
+    /// 踩中的地雷
+    pub boom: Option<Loc>,
+    /// 每个单元格为地雷的概率，与地图等长，叠加在未打开的单元格上，见`solver::probabilities`
+    pub probs: Option<&'a [f64]>,
+}
+
+impl Default for Options<'_> {
+    fn default() -> Self {
+        Self {
+            cell: 24,
+            labels: false,
+            layout: false,
+            boom: None,
+            probs: None,
+        }
+    }
+}
+
+/// 渲染对局，踩中的地雷取自对局
+pub fn render_game(game: &Game, opts: &Options) -> String {
+    let opts = Options {
+        boom: opts.boom.or(game.boom()),
+        ..*opts
+    };
+    render(game.map(), &opts)
+}
+
+/// 渲染地图
+pub fn render(map: &MineMap, opts: &Options) -> String {
+    let (w, h) = (map.width as u32, map.height as u32);
+    let c = opts.cell.max(8);
+    let margin = if opts.labels { c } else { 0 };
+    let (width, height) = (margin + w * c, margin + h * c);
+    // 已踩雷时地雷均已打开
+    let over = opts.layout || map.iter().any(|c| c.is_reveal() && c.is_mine());
+    let probs = opts.probs.filter(|p| p.len() == map.map.len());
+
+    let mut buf = String::new();
+    let _ = writeln!(
+        buf,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+    );
+    let _ = writeln!(
+        buf,
+        r#"<rect width="{width}" height="{height}" fill="{FACE}"/>"#
+    );
+    if opts.labels {
+        let size = c as f32 * 0.45;
+        let _ = writeln!(
+            buf,
+            r##"<g font-family="monospace" font-size="{size:.1}" fill="#404040" text-anchor="middle" dominant-baseline="central">"##
+        );
+        let mid = margin as f32 / 2.0;
+        for x in 0..w {
+            let cx = (margin + x * c) as f32 + c as f32 / 2.0;
+            let _ = writeln!(buf, r#"<text x="{cx:.1}" y="{mid:.1}">{x}</text>"#);
+        }
+        for y in 0..h {
+            let cy = (margin + y * c) as f32 + c as f32 / 2.0;
+            let _ = writeln!(buf, r#"<text x="{mid:.1}" y="{cy:.1}">{y}</text>"#);
+        }
+        buf.push_str("</g>\n");
+    }
+
+    for (i, cell) in map.iter().enumerate() {
+        let (x, y) = (i as u32 % w, i as u32 / w);
+        let (px, py) = (margin + x * c, margin + y * c);
+        let loc = Loc::from(x as usize, y as usize);
+        let open = cell.is_reveal() || (opts.layout && !cell.is_flagged());
+        if cell.is_flagged() {
+            raised(&mut buf, px, py, c);
+            flag(&mut buf, px, py, c);
+            if over && !cell.is_mine() {
+                cross(&mut buf, px, py, c);
+            }
+        } else if open {
+            let fill = if opts.boom == Some(loc) { BOOM } else { FACE };
+            let _ = writeln!(
+                buf,
+                r#"<rect x="{px}" y="{py}" width="{c}" height="{c}" fill="{fill}" stroke="{SHADOW}" stroke-width="1"/>"#
+            );
+            if cell.is_mine() {
+                mine(&mut buf, px, py, c);
+            } else {
+                warn(&mut buf, cell, px, py, c);
+            }
+        } else {
+            raised(&mut buf, px, py, c);
+            if let Some(p) = probs.map(|p| p[i]) {
+                heat(&mut buf, p, px, py, c);
+            }
+        }
+    }
+    buf.push_str("</svg>\n");
+    buf
+}
+
+/// 凸起的方块
+fn raised(buf: &mut String, px: u32, py: u32, c: u32) {
+    let b = (c / 8).max(2);
+    let _ = writeln!(
+        buf,
+        r#"<rect x="{px}" y="{py}" width="{c}" height="{c}" fill="{LIGHT}"/><path d="M{} {py}v{c}h-{c}z" fill="{SHADOW}"/><rect x="{}" y="{}" width="{}" height="{}" fill="{FACE}"/>"#,
+        px + c,
+        px + b,
+        py + b,
+        c - 2 * b,
+        c - 2 * b
+    );
+}
+
+fn warn(buf: &mut String, cell: Cell, px: u32, py: u32, c: u32) {
+    let v = cell.get_warn();
+    if v == 0 {
+        return;
+    }
+    let (cx, cy) = (px as f32 + c as f32 / 2.0, py as f32 + c as f32 / 2.0);
+    let _ = writeln!(
+        buf,
+        r#"<text x="{cx:.1}" y="{cy:.1}" font-family="monospace" font-weight="bold" font-size="{:.1}" fill="{}" text-anchor="middle" dominant-baseline="central">{v}</text>"#,
+        c as f32 * 0.7,
+        WARN_COLORS[v as usize - 1]
+    );
+}
+
+fn mine(buf: &mut String, px: u32, py: u32, c: u32) {
+    let c = c as f32;
+    let (cx, cy) = (px as f32 + c / 2.0, py as f32 + c / 2.0);
+    let (r, s) = (c * 0.25, c * 0.36);
+    let _ = writeln!(
+        buf,
+        r#"<g stroke="black" stroke-width="{:.1}"><path d="M{:.1} {cy:.1}h{:.1}M{cx:.1} {:.1}v{:.1}M{:.1} {:.1}l{:.1} {:.1}M{:.1} {:.1}l{:.1} -{:.1}"/></g><circle cx="{cx:.1}" cy="{cy:.1}" r="{r:.1}"/><rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{LIGHT}"/>"#,
+        c / 12.0,
+        cx - s,
+        s * 2.0,
+        cy - s,
+        s * 2.0,
+        cx - s * 0.7,
+        cy - s * 0.7,
+        s * 1.4,
+        s * 1.4,
+        cx - s * 0.7,
+        cy + s * 0.7,
+        s * 1.4,
+        s * 1.4,
+        cx - r * 0.5,
+        cy - r * 0.5,
+        r * 0.35,
+        r * 0.35
+    );
+}
+
+fn flag(buf: &mut String, px: u32, py: u32, c: u32) {
+    let c = c as f32;
+    let (px, py) = (px as f32, py as f32);
+    let pole = px + c * 0.55;
+    let _ = writeln!(
+        buf,
+        r#"<path d="M{pole:.1} {:.1}L{:.1} {:.1}L{pole:.1} {:.1}z" fill="red"/><path d="M{pole:.1} {:.1}V{:.1}" stroke="black" stroke-width="{:.1}"/><rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}"/>"#,
+        py + c * 0.2,
+        px + c * 0.25,
+        py + c * 0.35,
+        py + c * 0.5,
+        py + c * 0.2,
+        py + c * 0.72,
+        c / 12.0,
+        px + c * 0.27,
+        py + c * 0.7,
+        c * 0.5,
+        c * 0.1
+    );
+}
+
+/// 插错的旗帜上画叉
+fn cross(buf: &mut String, px: u32, py: u32, c: u32) {
+    let c = c as f32;
+    let (x0, y0, d) = (px as f32 + c * 0.15, py as f32 + c * 0.15, c * 0.7);
+    let _ = writeln!(
+        buf,
+        r#"<path d="M{x0:.1} {y0:.1}l{d:.1} {d:.1}M{x0:.1} {:.1}l{d:.1} -{d:.1}" stroke="{BOOM}" stroke-width="{:.1}"/>"#,
+        y0 + d,
+        c / 10.0
+    );
+}
+
+/// 概率热力图：0为绿色，0.5为黄色，1为红色
+fn heat(buf: &mut String, p: f64, px: u32, py: u32, c: u32) {
+    let p = p.clamp(0.0, 1.0);
+    let r = (p * 2.0).min(1.0) * 255.0;
+    let g = ((1.0 - p) * 2.0).min(1.0) * 255.0;
+    let _ = writeln!(
+        buf,
+        r#"<rect x="{px}" y="{py}" width="{c}" height="{c}" fill="rgb({r:.0},{g:.0},0)" fill-opacity="0.5"><title>{:.1}%</title></rect>"#,
+        p * 100.0
+    );
+}
@@ -0,0 +1,220 @@
+use std::{fmt::Write, thread, time::Instant};
+
+use crate::{
+    bot::Bot,
+    game::{Action, Game, Status},
+    location::Loc,
+    mmap::MineMap,
+};
+
+/// 单局结果
+#[derive(Debug, Clone, Copy)]
+pub struct Outcome {
+    pub won: bool,
+    pub bbbv: usize,
+    /// 用时（秒）
+    pub secs: f64,
+    /// 结束时的进度，见`Game::progress`
+    pub progress: f64,
+    /// 猜测次数
+    pub guesses: u32,
+}
+
+/// 均值及其95%置信区间
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Interval {
+    pub mean: f64,
+    pub lo: f64,
+    pub hi: f64,
+}
+
+impl Interval {
+    /// 基于正态近似估计样本均值的区间
+    fn of_mean(ls: &[f64]) -> Self {
+        let n = ls.len() as f64;
+        if ls.is_empty() {
+            return Self::default();
+        }
+        let mean = ls.iter().sum::<f64>() / n;
+        if ls.len() < 2 {
+            return Self {
+                mean,
+                lo: mean,
+                hi: mean,
+            };
+        }
+        let var = ls.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        let d = Z95 * (var / n).sqrt();
+        Self {
+            mean,
+            lo: mean - d,
+            hi: mean + d,
+        }
+    }
+
+    /// Wilson区间，适用于胜率这类比例
+    fn of_rate(hit: usize, n: usize) -> Self {
+        if n == 0 {
+            return Self::default();
+        }
+        let (p, n) = (hit as f64 / n as f64, n as f64);
+        let z2 = Z95 * Z95;
+        let den = 1.0 + z2 / n;
+        let mid = (p + z2 / (2.0 * n)) / den;
+        let d = Z95 * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / den;
+        Self {
+            mean: p,
+            lo: mid - d,
+            hi: mid + d,
+        }
+    }
+}
+
+const Z95: f64 = 1.96;
+
+/// 某个机器人在一组对局中的统计
+#[derive(Debug, Clone)]
+pub struct Summary {
+    pub bot: String,
+    pub games: usize,
+    pub wins: usize,
+    pub win_rate: Interval,
+    /// 胜局的 3BV/s
+    pub bbbv_per_sec: Interval,
+    /// 败局结束时的进度
+    pub progress_at_death: Interval,
+    /// 每局猜测次数
+    pub guesses: Interval,
+}
+
+impl Summary {
+    fn of(bot: &str, ls: &[Outcome]) -> Self {
+        let wins = ls.iter().filter(|o| o.won).count();
+        let speed: Vec<f64> = ls
+            .iter()
+            .filter(|o| o.won && o.secs > 0.0)
+            .map(|o| o.bbbv as f64 / o.secs)
+            .collect();
+        let death: Vec<f64> = ls.iter().filter(|o| !o.won).map(|o| o.progress).collect();
+        let guesses: Vec<f64> = ls.iter().map(|o| o.guesses as f64).collect();
+        Self {
+            bot: bot.to_string(),
+            games: ls.len(),
+            wins,
+            win_rate: Interval::of_rate(wins, ls.len()),
+            bbbv_per_sec: Interval::of_mean(&speed),
+            progress_at_death: Interval::of_mean(&death),
+            guesses: Interval::of_mean(&guesses),
+        }
+    }
+}
+
+/// 让机器人完成一局
+/// - 首次打开固定在中心，使所有机器人面对相同的布局
+/// - 操作数超过单元格数的4倍仍未结束时判负
+pub fn play(
+    bot: &dyn Bot,
+    count: u16,
+    width: u8,
+    height: u8,
+    seed: u64,
+) -> Result<Outcome, String> {
+    let mut game = Game::by_seed(MineMap::new(count, width, height)?, seed);
+    let tt = Instant::now();
+    game.act(Action::Reveal(Loc(width / 2, height / 2)));
+    let mut guesses = 0;
+    let limit = width as usize * height as usize * 4;
+    for _ in 0..limit {
+        if game.is_over() {
+            break;
+        }
+        let d = bot.decide(width, height, &game.view());
+        if d.guess {
+            guesses += 1;
+        }
+        game.act(d.action);
+    }
+    let secs = tt.elapsed().as_secs_f64();
+    Ok(Outcome {
+        won: game.status() == Status::Won,
+        bbbv: game.map().count_3bv(),
+        secs,
+        progress: game.progress(),
+        guesses,
+    })
+}
+
+/// # 锦标赛
+/// 每个机器人使用相同的种子列表各玩一遍，按线程平分种子并行执行
+/// # Returns
+/// - 与`bots`顺序一致的统计
+pub fn run(
+    bots: &[&dyn Bot],
+    count: u16,
+    width: u8,
+    height: u8,
+    seeds: &[u64],
+) -> Result<Vec<Summary>, String> {
+    // 提前检查参数，避免在线程中报错
+    MineMap::new(count, width, height)?;
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk = seeds.len().div_ceil(threads).max(1);
+    let parts = thread::scope(|s| {
+        let handles: Vec<_> = seeds
+            .chunks(chunk)
+            .map(|part| {
+                s.spawn(move || {
+                    bots.iter()
+                        .map(|&b| {
+                            part.iter()
+                                .map(|&seed| play(b, count, width, height, seed))
+                                .collect::<Result<Vec<_>, _>>()
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| {
+                h.join()
+                    .unwrap_or_else(|_| Err("对局线程异常退出！".to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()
+    })?;
+    let mut merged = vec![Vec::with_capacity(seeds.len()); bots.len()];
+    for part in parts {
+        for (all, ls) in merged.iter_mut().zip(part) {
+            all.extend(ls);
+        }
+    }
+    Ok(bots
+        .iter()
+        .zip(&merged)
+        .map(|(b, ls)| Summary::of(b.name(), ls))
+        .collect())
+}
+
+/// 输出对比表格
+pub fn format_table(ls: &[Summary]) -> String {
+    let mut buf = String::new();
+    let _ = writeln!(
+        buf,
+        "{:<12} {:>6} {:>22} {:>26} {:>22} {:>22}",
+        "bot", "games", "win rate", "3BV/s", "progress at death", "guesses"
+    );
+    let fmt = |i: &Interval, p: usize| format!("{:.p$} [{:.p$}, {:.p$}]", i.mean, i.lo, i.hi);
+    for s in ls {
+        let _ = writeln!(
+            buf,
+            "{:<12} {:>6} {:>22} {:>26} {:>22} {:>22}",
+            s.bot,
+            s.games,
+            fmt(&s.win_rate, 3),
+            fmt(&s.bbbv_per_sec, 1),
+            fmt(&s.progress_at_death, 3),
+            fmt(&s.guesses, 2),
+        );
+    }
+    buf
+}
@@ -0,0 +1,330 @@
+//! # 联机对战协议
+//! 基于TCP，每条消息为一帧：
+//! - `len: u32` 大端序，其后内容的字节数
+//! - `version: u8` 协议版本，须与`VERSION`相同
+//! - `tag: u8` 消息类型，见`Msg`
+//! - 消息内容：整数为大端序，字符串为`len: u16`加UTF-8字节
+//!
+//! 同一局中所有玩家使用相同种子生成的布局，并从同一单元格开始。
+//! 布局只保存在服务端，客户端只收到自己的玩家视角。
+
+use std::io::{self, Read, Write};
+
+use crate::{
+    game::{Action, Status},
+    location::Loc,
+};
+
+pub const VERSION: u8 = 1;
+/// 单帧长度上限
+pub const MAX_FRAME: usize = 64 * 1024;
+/// 玩家数范围
+pub const MIN_PLAYERS: u8 = 2;
+pub const MAX_PLAYERS: u8 = 8;
+
+/// 一名玩家的最终成绩
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Standing {
+    pub id: u8,
+    pub status: Status,
+    /// 结束时距开局的毫秒数，未结束为0
+    pub ms: u32,
+    /// 进度百分比
+    pub percent: u8,
+}
+
+/// # 消息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Msg {
+    /// 客户端 -> 服务端：加入
+    Hello {
+        name: String,
+    },
+    /// 客户端 -> 服务端：操作
+    Act(Action),
+    /// 服务端 -> 客户端：已加入，`need`为开局所需的玩家数
+    Welcome {
+        id: u8,
+        need: u8,
+    },
+    /// 服务端 -> 全体：开局
+    Start {
+        width: u8,
+        height: u8,
+        count: u16,
+        /// 已为所有玩家打开的起始单元格
+        start: Loc,
+        /// `(id, 名字)`
+        players: Vec<(u8, String)>,
+    },
+    /// 服务端 -> 客户端：自己的玩家视角，见`MineMap::view`，全部玩家结束后为完整布局
+    View {
+        status: Status,
+        cells: Vec<u8>,
+    },
+    /// 服务端 -> 全体：进度百分比
+    Progress {
+        id: u8,
+        percent: u8,
+    },
+    /// 服务端 -> 全体：踩雷
+    Dead {
+        id: u8,
+        ms: u32,
+    },
+    /// 服务端 -> 全体：完成
+    Finish {
+        id: u8,
+        ms: u32,
+    },
+    /// 服务端 -> 全体：断开连接
+    Left {
+        id: u8,
+    },
+    /// 服务端 -> 全体：比赛结束，按名次排列
+    End {
+        standings: Vec<Standing>,
+    },
+    Error {
+        msg: String,
+    },
+}
+
+fn status_to_u8(s: Status) -> u8 {
+    match s {
+        Status::Ready => 0,
+        Status::Playing => 1,
+        Status::Won => 2,
+        Status::Lost => 3,
+    }
+}
+
+fn status_from_u8(v: u8) -> Result<Status, String> {
+    Ok(match v {
+        0 => Status::Ready,
+        1 => Status::Playing,
+        2 => Status::Won,
+        3 => Status::Lost,
+        _ => return Err(format!("未知的对局状态：{v}")),
+    })
+}
+
+fn put_str(buf: &mut Vec<u8>, s: &str) {
+    let mut n = s.len().min(u16::MAX as usize);
+    while !s.is_char_boundary(n) {
+        n -= 1;
+    }
+    let bs = &s.as_bytes()[..n];
+    buf.extend((bs.len() as u16).to_be_bytes());
+    buf.extend(bs);
+}
+
+/// 按顺序读取消息内容
+struct Cursor<'a>(&'a [u8]);
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        if self.0.len() < n {
+            return Err("消息内容不完整！".to_string());
+        }
+        let (a, b) = self.0.split_at(n);
+        self.0 = b;
+        Ok(a)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        let b = self.take(2)?;
+        Ok(u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let b = self.take(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn str(&mut self) -> Result<String, String> {
+        let n = self.u16()? as usize;
+        String::from_utf8(self.take(n)?.to_vec()).map_err(|_| "字符串不是UTF-8！".to_string())
+    }
+}
+
+impl Msg {
+    fn tag(&self) -> u8 {
+        match self {
+            Msg::Hello { .. } => 1,
+            Msg::Act(_) => 2,
+            Msg::Welcome { .. } => 16,
+            Msg::Start { .. } => 17,
+            Msg::View { .. } => 18,
+            Msg::Progress { .. } => 19,
+            Msg::Dead { .. } => 20,
+            Msg::Finish { .. } => 21,
+            Msg::Left { .. } => 22,
+            Msg::End { .. } => 23,
+            Msg::Error { .. } => 31,
+        }
+    }
+
+    /// 编码为完整的一帧
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = vec![0, 0, 0, 0, VERSION, self.tag()];
+        match self {
+            Msg::Hello { name } => put_str(&mut buf, name),
+            Msg::Act(a) => {
+                let kind = match a {
+                    Action::Reveal(_) => 0,
+                    Action::Flag(_) => 1,
+                    Action::Chord(_) => 2,
+                };
+                let Loc(x, y) = a.loc();
+                buf.extend([kind, x, y]);
+            }
+            Msg::Welcome { id, need } => buf.extend([*id, *need]),
+            Msg::Start {
+                width,
+                height,
+                count,
+                start,
+                players,
+            } => {
+                buf.extend([*width, *height]);
+                buf.extend(count.to_be_bytes());
+                buf.extend([start.0, start.1, players.len() as u8]);
+                for (id, name) in players {
+                    buf.push(*id);
+                    put_str(&mut buf, name);
+                }
+            }
+            Msg::View { status, cells } => {
+                buf.push(status_to_u8(*status));
+                buf.extend((cells.len() as u32).to_be_bytes());
+                buf.extend(cells);
+            }
+            Msg::Progress { id, percent } => buf.extend([*id, *percent]),
+            Msg::Dead { id, ms } | Msg::Finish { id, ms } => {
+                buf.push(*id);
+                buf.extend(ms.to_be_bytes());
+            }
+            Msg::Left { id } => buf.push(*id),
+            Msg::End { standings } => {
+                buf.push(standings.len() as u8);
+                for s in standings {
+                    buf.extend([s.id, status_to_u8(s.status)]);
+                    buf.extend(s.ms.to_be_bytes());
+                    buf.push(s.percent);
+                }
+            }
+            Msg::Error { msg } => put_str(&mut buf, msg),
+        }
+        let len = (buf.len() - 4) as u32;
+        buf[..4].copy_from_slice(&len.to_be_bytes());
+        buf
+    }
+
+    /// 解码一帧中长度之后的部分
+    pub fn decode(frame: &[u8]) -> Result<Self, String> {
+        let mut c = Cursor(frame);
+        let version = c.u8()?;
+        if version != VERSION {
+            return Err(format!("协议版本不符：{version}，应为{VERSION}"));
+        }
+        let msg = match c.u8()? {
+            1 => Msg::Hello { name: c.str()? },
+            2 => {
+                let (kind, loc) = (c.u8()?, Loc(c.u8()?, c.u8()?));
+                Msg::Act(match kind {
+                    0 => Action::Reveal(loc),
+                    1 => Action::Flag(loc),
+                    2 => Action::Chord(loc),
+                    k => return Err(format!("未知的操作：{k}")),
+                })
+            }
+            16 => Msg::Welcome {
+                id: c.u8()?,
+                need: c.u8()?,
+            },
+            17 => {
+                let (width, height, count) = (c.u8()?, c.u8()?, c.u16()?);
+                let start = Loc(c.u8()?, c.u8()?);
+                let n = c.u8()?;
+                let mut players = Vec::with_capacity(n as usize);
+                for _ in 0..n {
+                    players.push((c.u8()?, c.str()?));
+                }
+                Msg::Start {
+                    width,
+                    height,
+                    count,
+                    start,
+                    players,
+                }
+            }
+            18 => {
+                let status = status_from_u8(c.u8()?)?;
+                let n = c.u32()? as usize;
+                Msg::View {
+                    status,
+                    cells: c.take(n)?.to_vec(),
+                }
+            }
+            19 => Msg::Progress {
+                id: c.u8()?,
+                percent: c.u8()?,
+            },
+            20 => Msg::Dead {
+                id: c.u8()?,
+                ms: c.u32()?,
+            },
+            21 => Msg::Finish {
+                id: c.u8()?,
+                ms: c.u32()?,
+            },
+            22 => Msg::Left { id: c.u8()? },
+            23 => {
+                let n = c.u8()?;
+                let mut standings = Vec::with_capacity(n as usize);
+                for _ in 0..n {
+                    standings.push(Standing {
+                        id: c.u8()?,
+                        status: status_from_u8(c.u8()?)?,
+                        ms: c.u32()?,
+                        percent: c.u8()?,
+                    });
+                }
+                Msg::End { standings }
+            }
+            31 => Msg::Error { msg: c.str()? },
+            t => return Err(format!("未知的消息类型：{t}")),
+        };
+        if !c.0.is_empty() {
+            return Err(format!("消息多出{}字节！", c.0.len()));
+        }
+        Ok(msg)
+    }
+}
+
+/// 写入一条消息
+pub fn send(w: &mut impl Write, msg: &Msg) -> io::Result<()> {
+    w.write_all(&msg.encode())?;
+    w.flush()
+}
+
+/// 读取一条消息
+pub fn recv(r: &mut impl Read) -> io::Result<Msg> {
+    let mut len = [0; 4];
+    r.read_exact(&mut len)?;
+    let len = u32::from_be_bytes(len) as usize;
+    if len > MAX_FRAME {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("帧过长：{len}字节"),
+        ));
+    }
+    let mut frame = vec![0; len];
+    r.read_exact(&mut frame)?;
+    Msg::decode(&frame).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
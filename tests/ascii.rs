@@ -0,0 +1,67 @@
+use mines::{
+    ascii,
+    game::{Action, Game, Status},
+    location::Loc,
+    mmap::MineMap,
+};
+
+const BOARD: &str = "
+# 4x3，两颗地雷
+1 1 0 0
+F 1 1 .
+. . . *
+";
+
+#[test]
+fn parse_with_progress() {
+    let map: MineMap = BOARD.parse().unwrap();
+    assert_eq!((map.width, map.height, map.count), (4, 3, 2));
+    let flag = map.get_by_loc(Loc(0, 1)).unwrap();
+    assert!(flag.is_mine() && flag.is_flagged());
+    assert!(map.get(3, 0).unwrap().is_reveal());
+    assert!(!map.get(3, 1).unwrap().is_reveal());
+    assert_eq!(map.get(2, 2).unwrap().get_warn(), 1);
+    // 字符画可以还原
+    assert_eq!(
+        ascii::parse(&ascii::format(&map)).unwrap().export(true),
+        map.export(true)
+    );
+    assert_eq!(Game::by_map(map).progress(), 0.6);
+}
+
+#[test]
+fn reject_inconsistent_digit() {
+    let err = ascii::parse("2 .\n* .").err().unwrap();
+    assert!(err.contains("(0, 0)"), "{err}");
+}
+
+#[test]
+fn reject_bad_shape_and_chars() {
+    assert!(ascii::parse("* .\n. . .").is_err());
+    assert!(ascii::parse("* ?\n. .").is_err());
+}
+
+#[test]
+fn round_trip_game_in_progress() {
+    let mut g = Game::by_map(BOARD.parse().unwrap());
+    // 错误标记与踩中的地雷
+    g.act(Action::Flag(Loc(3, 1)));
+    g.act(Action::Reveal(Loc(0, 2)));
+    let text = ascii::format(g.map());
+    assert_eq!(text, "1 1 0 0\nF 1 1 f\n1 . . *\n");
+    let map = ascii::parse(&text).unwrap();
+    assert_eq!(map.export(true), g.map().export(true));
+    assert!(map.get(3, 1).unwrap().is_flagged());
+    assert!(!map.get(3, 1).unwrap().is_mine());
+
+    g.act(Action::Reveal(Loc(3, 2)));
+    assert_eq!(g.status(), Status::Lost);
+    let text = ascii::format(g.map());
+    assert_eq!(text, "1 1 0 0\nF 1 1 f\n1 . . X\n");
+    let map = ascii::parse(&text).unwrap();
+    assert!(map.get(3, 2).unwrap().is_reveal());
+    // 插旗的地雷不保留打开状态
+    let mut lost = g.map().export(true);
+    lost[2 + 4] &= !0x80;
+    assert_eq!(map.export(true), lost);
+}
@@ -0,0 +1,39 @@
+use mines::difficulty::{validate, Difficulty};
+
+#[test]
+fn presets() {
+    assert_eq!(Difficulty::Expert.size(), (30, 16, 99));
+    assert_eq!("i".parse(), Ok(Difficulty::Intermediate));
+    assert_eq!("9x9/10".parse(), Ok(Difficulty::Beginner));
+    assert_eq!(Difficulty::custom(16, 16, 40), Ok(Difficulty::Intermediate));
+    let d: Difficulty = "20X10/30".parse().unwrap();
+    assert_eq!(d.to_string(), "20x10/30");
+    assert_eq!(d.name(), "custom");
+    let m = Difficulty::Expert.map().unwrap();
+    assert_eq!((m.width, m.height, m.count), (30, 16, 99));
+}
+
+#[test]
+fn density() {
+    // 9x9留出3x3后最多72颗
+    assert!(validate(9, 9, 72).is_ok());
+    assert!(validate(9, 9, 73).is_err());
+    // 窄条的安全区为2x3
+    assert!(validate(2, 5, 4).is_ok());
+    assert!(validate(2, 5, 5).is_err());
+    assert!(validate(1, 9, 1).is_err());
+    assert!(validate(9, 9, 0).is_err());
+    assert!("hard".parse::<Difficulty>().is_err());
+    assert!("9x9/80".parse::<Difficulty>().is_err());
+}
+
+#[test]
+fn density_by_safety() {
+    use mines::{difficulty::validate_with, mmap::Safety};
+    assert!(validate_with(9, 9, 80, Safety::Cell).is_ok());
+    assert!(validate_with(9, 9, 80, Safety::Opening).is_err());
+    assert!(validate_with(9, 9, 56, Safety::Radius(2)).is_ok());
+    assert!(validate_with(9, 9, 57, Safety::Radius(2)).is_err());
+    assert!(validate_with(2, 2, 3, Safety::None).is_ok());
+    assert!(validate_with(2, 2, 4, Safety::None).is_err());
+}
@@ -0,0 +1,95 @@
+use mines::{
+    env::{Env, Rewards, CHANNELS},
+    game::{Action, Status},
+    location::Loc,
+};
+
+/// 通道`ch`中第`i`个单元格的值
+fn at(obs: &[f32], ch: usize, i: usize, size: usize) -> f32 {
+    obs[ch * size + i]
+}
+
+#[test]
+fn reset_and_observe() {
+    let mut env = Env::new(10, 9, 9, Rewards::default()).unwrap();
+    assert_eq!(env.action_space(), 162);
+    assert_eq!(env.observation_shape(), [CHANNELS, 9, 9]);
+    assert_eq!(env.decode_action(10), Some(Action::Reveal(Loc(1, 1))));
+    assert_eq!(env.decode_action(81 + 9), Some(Action::Flag(Loc(0, 1))));
+    assert_eq!(env.decode_action(162), None);
+
+    let obs = env.reset(1);
+    assert_eq!(obs.len(), CHANNELS * 81);
+    // 全部未打开
+    assert!((0..81).all(|i| at(&obs, 0, i, 81) == 1.0));
+    assert_eq!(obs.iter().sum::<f32>(), 81.0);
+    assert_eq!(env.game().status(), Status::Ready);
+}
+
+#[test]
+fn step_rewards_and_done() {
+    let r = Rewards {
+        step: -0.001,
+        ..Default::default()
+    };
+    let mut env = Env::new(10, 9, 9, r).unwrap();
+    env.reset(7);
+    let (obs, reward, done, info) = env.step(4 * 9 + 4);
+    assert!(info.valid && !done);
+    assert_eq!(info.status, Status::Playing);
+    assert!(info.revealed > 1);
+    assert_eq!(reward, r.step + r.reveal * info.revealed as f32);
+    // 起点为空白，落在数字0通道
+    assert_eq!(at(&obs, 2, 4 * 9 + 4, 81), 1.0);
+    // 每个单元格恰好一个通道为1
+    assert_eq!(obs.iter().sum::<f32>(), 81.0);
+
+    // 重复打开为无效操作
+    let (_, reward, done, info) = env.step(4 * 9 + 4);
+    assert!(!info.valid && !done);
+    assert_eq!((info.revealed, reward), (0, r.step + r.noop));
+
+    // 插旗
+    let mine = env.game().map().iter().position(|c| c.is_mine()).unwrap();
+    let mut obs = vec![0.0; CHANNELS * 81];
+    let (reward, done, info) = env.step_into(81 + mine, &mut obs);
+    assert!(info.valid && !done);
+    assert_eq!(reward, r.step);
+    assert_eq!(at(&obs, 1, mine, 81), 1.0);
+
+    // 拔旗后踩雷，结束
+    env.step_into(81 + mine, &mut obs);
+    let (reward, done, info) = env.step_into(mine, &mut obs);
+    assert!(done);
+    assert_eq!(info.status, Status::Lost);
+    assert_eq!(reward, r.step + r.lose);
+    // 结束后的操作无效，不再给出胜负奖励
+    let (_, reward, done, _) = env.step(0);
+    assert!(done);
+    assert_eq!(reward, r.step + r.noop);
+
+    // 重置后可以继续
+    env.reset(7);
+    assert_eq!(env.game().status(), Status::Ready);
+}
+
+#[test]
+fn win_reward() {
+    let r = Rewards::default();
+    let mut env = Env::new(1, 2, 2, r).unwrap();
+    env.reset(3);
+    let (_, _, done, info) = env.step(0);
+    assert!(!done && info.valid);
+    let safe: Vec<usize> = (1..4)
+        .filter(|&i| !env.game().map().iter().nth(i).unwrap().is_mine())
+        .collect();
+    assert_eq!(safe.len(), 2);
+    let (_, reward, done, _) = env.step(safe[0]);
+    assert!(!done);
+    assert_eq!(reward, r.reveal);
+    let (_, reward, done, info) = env.step(safe[1]);
+    assert!(done);
+    assert_eq!(info.status, Status::Won);
+    assert_eq!(info.progress, 1.0);
+    assert_eq!(reward, r.reveal + r.win);
+}
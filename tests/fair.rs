@@ -0,0 +1,104 @@
+use mines::{
+    fair::{self, Commitment, Dealer, Opening},
+    game::Action,
+    location::Loc,
+    mmap::Safety,
+};
+
+fn hex(bs: &[u8]) -> String {
+    bs.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[test]
+fn sha256_vectors() {
+    assert_eq!(
+        hex(&fair::sha256(b"")),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    assert_eq!(
+        hex(&fair::sha256(b"abc")),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    let long = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+    assert_eq!(
+        hex(&fair::sha256(long)),
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+    );
+}
+
+/// 打开全部单元格直到结束，记录每步的回应
+fn play(d: &mut Dealer) -> Vec<(Action, Vec<u8>)> {
+    let w = d.commitment().width as usize;
+    let size = d.game().map().map.len();
+    let mut transcript = Vec::new();
+    for i in 0..size {
+        if d.game().is_over() {
+            break;
+        }
+        let a = Action::Reveal(Loc::from(i % w, i / w));
+        transcript.push((a, d.act(a)));
+    }
+    transcript
+}
+
+#[test]
+fn honest_dealer_verifies() {
+    let mut d = Dealer::new(10, 9, 9, Some(Loc(4, 4)), Safety::Opening).unwrap();
+    assert!(d.opening().is_none());
+    let transcript = play(&mut d);
+    let opening = d.opening().unwrap();
+    fair::verify(d.commitment(), &opening, &transcript).unwrap();
+
+    // 公布错误的种子
+    let wrong = Opening {
+        seed: opening.seed ^ 1,
+        ..opening
+    };
+    assert!(fair::verify(d.commitment(), &wrong, &transcript).is_err());
+}
+
+#[test]
+fn detect_moved_mines() {
+    let opening = Opening {
+        seed: 11,
+        nonce: [7; 16],
+    };
+    let honest = Dealer::by_opening(10, 9, 9, Some(Loc(4, 4)), Safety::Opening, opening).unwrap();
+    // 实际按另一种子的布局回应
+    let mut cheat = Dealer::by_opening(
+        10,
+        9,
+        9,
+        Some(Loc(4, 4)),
+        Safety::Opening,
+        Opening {
+            seed: 12,
+            ..opening
+        },
+    )
+    .unwrap();
+    let transcript = play(&mut cheat);
+    let err = fair::verify(honest.commitment(), &opening, &transcript)
+        .err()
+        .unwrap();
+    assert!(err.contains("回应"));
+}
+
+#[test]
+fn commitment_binds_safety() {
+    let opening = Opening {
+        seed: 3,
+        nonce: [1; 16],
+    };
+    let mut d = Dealer::by_opening(20, 9, 9, Some(Loc(4, 4)), Safety::Radius(2), opening).unwrap();
+    assert_eq!(d.commitment().safety, Safety::Radius(2));
+    assert_eq!(d.game().safety(), Safety::Radius(2));
+    let transcript = play(&mut d);
+    fair::verify(d.commitment(), &opening, &transcript).unwrap();
+    // 篡改承诺中的保护策略
+    let claimed = Commitment {
+        safety: Safety::Opening,
+        ..*d.commitment()
+    };
+    assert!(fair::verify(&claimed, &opening, &transcript).is_err());
+}
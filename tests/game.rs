@@ -0,0 +1,131 @@
+use mines::{
+    bot::{Blind, Bot, Simple},
+    game::{Action, Game, Status},
+    location::Loc,
+    mmap::MineMap,
+    tourney,
+};
+
+/// 3x2，地雷位于(1,0)，其余单元格均为1
+fn board() -> MineMap {
+    ". * .\n. . .".parse().unwrap()
+}
+
+#[test]
+fn first_click_starts_game() {
+    let mut g = Game::by_seed(MineMap::new(10, 9, 9).unwrap(), 5);
+    assert_eq!(g.status(), Status::Ready);
+    // 开局前插旗、双击无效
+    assert_eq!(g.act(Action::Flag(Loc(0, 0))), 0);
+    assert_eq!(g.act(Action::Chord(Loc(0, 0))), 0);
+    assert_eq!((g.actions(), g.history().len()), (0, 0));
+
+    let n = g.act(Action::Reveal(Loc(4, 4)));
+    assert!(n > 1);
+    assert_eq!(g.status(), Status::Playing);
+    assert!(g.map().get(4, 4).unwrap().is_empty());
+    assert_eq!(g.map().iter().filter(|c| c.is_mine()).count(), 10);
+    assert_eq!((g.actions(), g.history().len()), (1, 1));
+    assert_eq!(g.history()[0].action, Action::Reveal(Loc(4, 4)));
+
+    // 同一种子与起点得到相同布局
+    let mut h = Game::by_seed(MineMap::new(10, 9, 9).unwrap(), 5);
+    h.act(Action::Reveal(Loc(4, 4)));
+    assert_eq!(g.map().export(false), h.map().export(false));
+    // 越界无效
+    assert_eq!(h.act(Action::Reveal(Loc(9, 0))), 0);
+    assert_eq!(h.actions(), 1);
+}
+
+#[test]
+fn flag_chord_and_win() {
+    let mut g = Game::by_map(board());
+    assert_eq!(g.status(), Status::Playing);
+    assert_eq!(g.act(Action::Reveal(Loc(0, 0))), 1);
+    // 重复打开、插旗于已打开的单元格、旗数不符的双击都是无效操作
+    assert_eq!(g.act(Action::Reveal(Loc(0, 0))), 0);
+    assert_eq!(g.act(Action::Flag(Loc(0, 0))), 0);
+    assert_eq!(g.act(Action::Chord(Loc(0, 0))), 0);
+    assert_eq!(g.actions(), 1);
+
+    g.act(Action::Flag(Loc(1, 0)));
+    assert!(g.map().get(1, 0).unwrap().is_flagged());
+    // 插旗的单元格不能打开
+    assert_eq!(g.act(Action::Reveal(Loc(1, 0))), 0);
+    assert_eq!(g.actions(), 2);
+    // 旗数与数字相符，打开周围
+    assert_eq!(g.act(Action::Chord(Loc(0, 0))), 2);
+    assert_eq!(g.actions(), 3);
+    // 未打开的单元格不能双击
+    assert_eq!(g.act(Action::Chord(Loc(2, 0))), 0);
+    g.act(Action::Reveal(Loc(2, 0)));
+    assert_eq!(g.status(), Status::Playing);
+    g.act(Action::Reveal(Loc(2, 1)));
+    assert_eq!(g.status(), Status::Won);
+    assert!(g.is_over() && g.boom().is_none());
+    assert_eq!(g.progress(), 1.0);
+    // 结束后操作无效
+    assert_eq!(g.act(Action::Flag(Loc(1, 0))), 0);
+    assert_eq!((g.actions(), g.history().len()), (5, 5));
+}
+
+#[test]
+fn reveal_mine_loses() {
+    let mut g = Game::by_map(board());
+    g.act(Action::Reveal(Loc(0, 0)));
+    g.act(Action::Reveal(Loc(1, 0)));
+    assert_eq!(g.status(), Status::Lost);
+    assert_eq!(g.boom(), Some(Loc(1, 0)));
+    let t = g.elapsed();
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    assert_eq!(g.elapsed(), t);
+    assert_eq!(g.progress(), 0.2);
+
+    // 错误的旗使双击踩雷
+    let mut g = Game::by_map(board());
+    g.act(Action::Reveal(Loc(1, 1)));
+    g.act(Action::Flag(Loc(0, 0)));
+    g.act(Action::Chord(Loc(1, 1)));
+    assert_eq!(g.status(), Status::Lost);
+    assert_eq!(g.boom(), Some(Loc(1, 0)));
+}
+
+#[test]
+fn bots_and_tourney() {
+    let g = Game::by_map(board());
+    for bot in [&Blind as &dyn Bot, &Simple] {
+        let d = bot.decide(3, 2, &g.view());
+        let Loc(x, y) = d.action.loc();
+        assert!(x < 3 && y < 2);
+    }
+    // 数字1旁只剩一个未知单元格时插旗
+    let mut g = Game::by_map(board());
+    g.act(Action::Reveal(Loc(0, 0)));
+    g.act(Action::Reveal(Loc(0, 1)));
+    g.act(Action::Reveal(Loc(1, 1)));
+    g.act(Action::Reveal(Loc(2, 1)));
+    let d = Simple.decide(3, 2, &g.view());
+    assert!(!d.guess);
+    assert!(matches!(
+        d.action,
+        Action::Flag(Loc(1, 0)) | Action::Chord(_)
+    ));
+
+    let a = tourney::play(&Simple, 10, 9, 9, 3).unwrap();
+    let b = tourney::play(&Simple, 10, 9, 9, 3).unwrap();
+    assert_eq!((a.won, a.bbbv, a.guesses), (b.won, b.bbbv, b.guesses));
+    assert_eq!(a.progress, b.progress);
+
+    let seeds: Vec<u64> = (0..8).collect();
+    let ls = tourney::run(&[&Blind, &Simple], 10, 9, 9, &seeds).unwrap();
+    assert_eq!(ls.len(), 2);
+    assert_eq!(
+        (ls[0].bot.as_str(), ls[1].bot.as_str()),
+        ("blind", "simple")
+    );
+    assert!(ls.iter().all(|s| s.games == 8 && s.wins <= 8));
+    assert!(ls[1].wins >= ls[0].wins);
+    let table = tourney::format_table(&ls);
+    assert!(table.contains("blind") && table.contains("simple"));
+    assert!(tourney::run(&[&Simple], 0, 9, 9, &seeds).is_err());
+}
@@ -0,0 +1,289 @@
+use mines::{location::Loc, mmap::MineMap};
+
+fn mines_at(map: &MineMap) -> Vec<usize> {
+    map.iter()
+        .enumerate()
+        .filter(|(_, c)| c.is_mine())
+        .map(|(i, _)| i)
+        .collect()
+}
+
+#[test]
+fn densest_board_keeps_opening() {
+    // 9x9/72：安全区以外全部是雷
+    let mut map = MineMap::new(72, 9, 9).unwrap();
+    for seed in 0..20 {
+        map.new_game_by_seed(Some(Loc(4, 4)), seed);
+        let mines = mines_at(&map);
+        assert_eq!(mines.len(), 72);
+        for y in 3..6 {
+            for x in 3..6 {
+                assert!(!mines.contains(&(y * 9 + x)));
+            }
+        }
+    }
+}
+
+#[test]
+fn overfull_board_keeps_clicked_cell() {
+    // 4x4/15：安全区放不下，只保证点击的单元格
+    let mut map = MineMap::new(15, 4, 4).unwrap();
+    for seed in 0..20 {
+        map.new_game_by_seed(Some(Loc(1, 1)), seed);
+        assert_eq!(mines_at(&map).len(), 15);
+        assert!(!map.get(1, 1).unwrap().is_mine());
+        assert_eq!(map.get(1, 1).unwrap().get_warn(), 8);
+    }
+    map.new_game(Some(Loc(0, 0)));
+    assert_eq!(mines_at(&map).len(), 15);
+    assert!(!map.get(0, 0).unwrap().is_mine());
+}
+
+#[test]
+fn overflow_spreads_over_safe_area() {
+    // 5x5/18：安全区以外只有16格，其余2颗落在中心周围的8格中
+    let mut map = MineMap::new(18, 5, 5).unwrap();
+    let mut hit = [0; 25];
+    for seed in 0..200 {
+        map.new_game_by_seed(Some(Loc(2, 2)), seed);
+        let mines = mines_at(&map);
+        assert_eq!(mines.len(), 18);
+        for i in mines {
+            hit[i] += 1;
+        }
+    }
+    assert_eq!(hit[12], 0);
+    for y in 1..4 {
+        for x in 1..4 {
+            if (x, y) != (2, 2) {
+                // 期望为50次
+                assert!(hit[y * 5 + x] > 20, "({x}, {y}) {}", hit[y * 5 + x]);
+            }
+        }
+    }
+}
+
+#[test]
+fn custom_safe_area() {
+    let mut map = MineMap::new(10, 5, 5).unwrap();
+    let row: Vec<Loc> = (0..5).map(|x| Loc(x, 2)).collect();
+    map.new_game_by_area(&row, Some(7));
+    let mines = mines_at(&map);
+    assert_eq!(mines.len(), 10);
+    assert!(mines.iter().all(|i| i / 5 != 2));
+
+    let mut other = MineMap::new(10, 5, 5).unwrap();
+    other.new_game_by_area(&row, Some(7));
+    assert_eq!(mines_at(&other), mines);
+    // 越界坐标被忽略
+    other.new_game_by_area(&[Loc(9, 9)], None);
+    assert_eq!(mines_at(&other).len(), 10);
+}
+
+#[test]
+fn safety_policies() {
+    use mines::{
+        game::{Action, Game, Status},
+        mmap::Safety,
+    };
+    assert_eq!("cell".parse(), Ok(Safety::Cell));
+    assert_eq!("2".parse(), Ok(Safety::Radius(2)));
+    assert!("wide".parse::<Safety>().is_err());
+    assert_eq!(Safety::Radius(2).max_area(30, 4), 20);
+    assert_eq!(Safety::None.max_area(9, 9), 0);
+
+    // 半径2：以点击处为中心的5x5内没有雷
+    let mut map = MineMap::new(16, 9, 9).unwrap();
+    map.new_game_with(Some(Loc(4, 4)), Safety::Radius(2), Some(3));
+    let mines = mines_at(&map);
+    assert_eq!(mines.len(), 16);
+    assert!(mines
+        .iter()
+        .all(|&i| (i % 9).abs_diff(4) > 2 || (i / 9).abs_diff(4) > 2));
+
+    // 只保证点击的单元格：72颗以上的雷仍可开局
+    for seed in 0..10 {
+        let map = MineMap::new(80, 9, 9).unwrap();
+        let mut g = Game::by_seed(map, seed).with_safety(Safety::Cell);
+        g.act(Action::Reveal(Loc(0, 0)));
+        assert_eq!(g.status(), Status::Won);
+    }
+    // 不保护：满雷时首次打开必然踩雷
+    let map = MineMap::new(3, 2, 2).unwrap();
+    let mut hits = 0;
+    for seed in 0..20 {
+        let mut g = Game::by_seed(map.clone(), seed).with_safety(Safety::None);
+        g.act(Action::Reveal(Loc(0, 0)));
+        hits += (g.status() == Status::Lost) as usize;
+    }
+    assert!(hits > 0);
+}
+
+#[test]
+fn quality_metrics() {
+    use mines::quality::measure;
+    let map: MineMap = "* * . .\n* . . .\n. . . *\n. . . .".parse().unwrap();
+    let m = measure(&map, Some(Loc(3, 0)), true);
+    assert_eq!((m.max_cluster, m.openings, m.bbbv), (3, 2, map.count_3bv()));
+    // 二选一
+    let map: MineMap = ". . *\n. . .".parse().unwrap();
+    assert_eq!(measure(&map, Some(Loc(0, 0)), true).guesses, Some(1));
+    assert_eq!(measure(&map, Some(Loc(0, 0)), false).guesses, None);
+    let map: MineMap = "* . . .\n. . . .\n. . . .\n. . . .".parse().unwrap();
+    assert_eq!(measure(&map, Some(Loc(3, 3)), true).guesses, Some(0));
+    // 起点不安全时首次打开计为猜测
+    assert_eq!(measure(&map, Some(Loc(0, 0)), true).guesses, Some(1));
+}
+
+#[test]
+fn quality_filter() {
+    use mines::{mmap::Safety, quality::Filter};
+    let filter = Filter {
+        min_3bv: Some(30),
+        max_cluster: Some(3),
+        max_guesses: Some(0),
+        ..Default::default()
+    };
+    let mut map = MineMap::new(40, 16, 16).unwrap();
+    let start = Some(Loc(8, 8));
+    let r = map.new_game_filtered(start, Safety::Opening, Some(11), &filter);
+    assert!(r.accepted);
+    assert!(r.attempts >= 1);
+    assert!(r.metrics.bbbv >= 30 && r.metrics.max_cluster <= 3);
+    assert_eq!(r.metrics.guesses, Some(0));
+    assert_eq!(r.metrics.bbbv, map.count_3bv());
+    assert_eq!(mines_at(&map).len(), 40);
+
+    let mut other = MineMap::new(40, 16, 16).unwrap();
+    let r2 = other.new_game_filtered(start, Safety::Opening, Some(11), &filter);
+    assert_eq!(r, r2);
+    assert_eq!(mines_at(&other), mines_at(&map));
+    // 首次打开仍为空白
+    assert!(map.get(8, 8).unwrap().is_empty());
+
+    let impossible = Filter {
+        min_3bv: Some(1000),
+        max_attempts: 5,
+        ..Default::default()
+    };
+    let r = map.new_game_filtered(start, Safety::Opening, None, &impossible);
+    assert_eq!((r.attempts, r.accepted), (5, false));
+    assert_eq!(mines_at(&map).len(), 40);
+}
+
+#[test]
+fn distributions_keep_count_and_safe_zone() {
+    use mines::{distribution::Distribution, mmap::Safety, quality::measure};
+    let dists = [
+        Distribution::Uniform,
+        Distribution::Clustered { strength: 8.0 },
+        Distribution::Dispersed { strength: 8.0 },
+        Distribution::Gradient {
+            from: 0.0,
+            to: 1.0,
+            vertical: false,
+        },
+        Distribution::Weighted((0..480).map(|i| (i % 30 < 10) as u8 as f64).collect()),
+    ];
+    let mut clusters = [0; 5];
+    let mut map = MineMap::new(99, 30, 16).unwrap();
+    for (k, d) in dists.iter().enumerate() {
+        for seed in 0..10 {
+            map.new_game_by_distribution(Some(Loc(15, 8)), Safety::Opening, Some(seed), d)
+                .unwrap();
+            let mines = mines_at(&map);
+            assert_eq!(mines.len(), 99);
+            assert!(mines
+                .iter()
+                .all(|&i| (i % 30).abs_diff(15) > 1 || (i / 30).abs_diff(8) > 1));
+            clusters[k] += measure(&map, None, false).max_cluster;
+            match k {
+                // 左端权重为0
+                3 => assert!(mines.iter().all(|&i| i % 30 > 0)),
+                // 只有左侧三分之一有权重，160格足够布置
+                4 => assert!(mines.iter().all(|&i| i % 30 < 10)),
+                _ => {}
+            }
+        }
+    }
+    assert!(clusters[1] > clusters[0] && clusters[0] > clusters[2]);
+
+    // 有权重的单元格不足时，其余地雷均匀布置
+    let mut mask = vec![0.0; 81];
+    mask[0] = 1.0;
+    map = MineMap::new(10, 9, 9).unwrap();
+    map.new_game_by_distribution(
+        Some(Loc(8, 8)),
+        Safety::Opening,
+        Some(1),
+        &Distribution::Weighted(mask),
+    )
+    .unwrap();
+    assert_eq!(mines_at(&map).len(), 10);
+    assert!(map.get(0, 0).unwrap().is_mine());
+}
+
+#[test]
+fn weighted_pick_follows_weights() {
+    use mines::{distribution::Distribution, mmap::Safety};
+    // 下半部分权重为上半部分的3倍
+    let d = Distribution::Weighted((0..16).map(|i| if i < 8 { 1.0 } else { 3.0 }).collect());
+    let mut map = MineMap::new(1, 4, 4).unwrap();
+    let mut lower = 0;
+    for seed in 0..4000 {
+        map.new_game_by_distribution(None, Safety::None, Some(seed), &d)
+            .unwrap();
+        lower += (mines_at(&map)[0] >= 8) as usize;
+    }
+    // 期望为3000，标准差约27
+    assert!((2850..3150).contains(&lower), "{lower}");
+}
+
+#[test]
+fn zero_weight_spill_over_is_uniform() {
+    use mines::{distribution::Distribution, mmap::Safety};
+    // 只有3个正权重单元格，其余3颗地雷落在权重为0的13个单元格中
+    let mut ws = vec![0.0; 16];
+    (ws[0], ws[5], ws[10]) = (0.1, 0.2, 0.7);
+    let d = Distribution::Weighted(ws);
+    let mut map = MineMap::new(6, 4, 4).unwrap();
+    let mut hits = [0usize; 16];
+    for seed in 0..2000 {
+        map.new_game_by_distribution(None, Safety::None, Some(seed), &d)
+            .unwrap();
+        for i in mines_at(&map) {
+            hits[i] += 1;
+        }
+    }
+    for i in [0, 5, 10] {
+        assert_eq!(hits[i], 2000);
+    }
+    // 每个单元格期望约462次，标准差约19
+    for (i, &n) in hits.iter().enumerate() {
+        if ![0, 5, 10].contains(&i) {
+            assert!((380..540).contains(&n), "{i}: {n}");
+        }
+    }
+}
+
+#[test]
+fn distribution_validation() {
+    use mines::{distribution::Distribution, mmap::Safety};
+    let mut map = MineMap::new(10, 9, 9).unwrap();
+    let bad = [
+        Distribution::Weighted(vec![1.0; 80]),
+        Distribution::Weighted(vec![-1.0; 81]),
+        Distribution::Clustered { strength: f64::NAN },
+        Distribution::Gradient {
+            from: -1.0,
+            to: 1.0,
+            vertical: true,
+        },
+    ];
+    for d in &bad {
+        assert!(map
+            .new_game_by_distribution(None, Safety::Opening, None, d)
+            .is_err());
+    }
+    assert_eq!(mines_at(&map).len(), 0);
+}
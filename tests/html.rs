@@ -0,0 +1,29 @@
+use mines::{
+    game::{Action, Game},
+    html,
+    location::Loc,
+    mmap::MineMap,
+    replay::Replay,
+};
+
+#[test]
+fn export_finished_game() {
+    let map: MineMap = "* . . .\n. . . .\n. . . *".parse().unwrap();
+    let mut game = Game::by_map(map);
+    for a in [
+        Action::Reveal(Loc(2, 0)),
+        Action::Flag(Loc(0, 0)),
+        Action::Chord(Loc(1, 1)),
+    ] {
+        game.act(a);
+    }
+    let page = html::export(&Replay::from_game(&game));
+    assert!(page.contains("<title>Minesweeper - WON</title>"));
+    assert!(page.contains("const W = 4, H = 3;"));
+    assert!(page.contains("const MINES = [1,0,0,0,0,0,0,0,0,0,0,1];"));
+    assert!(page.contains(r#"[0,"f",0,0],"#));
+    assert!(page.contains("<tr><th>Clicks</th><td>3</td></tr>"));
+    assert!(!page.contains('@'));
+    // 除SVG命名空间外不引用外部资源
+    assert_eq!(page.matches("http").count(), 1);
+}
@@ -0,0 +1,66 @@
+use mines::{location::Loc, mbf, mmap::MineMap};
+
+fn board(w: u8, h: u8, c: u16, seed: u64) -> MineMap {
+    let mut m = MineMap::new(c, w, h).unwrap();
+    m.new_game_by_seed(Some(Loc(0, 0)), seed);
+    m
+}
+
+#[test]
+fn round_trip() {
+    for (i, (w, h, c)) in [(9, 9, 10), (16, 16, 40), (30, 16, 99)]
+        .into_iter()
+        .enumerate()
+    {
+        let m = board(w, h, c, i as u64);
+        let data = mbf::export(&m).unwrap();
+        assert_eq!(data.len(), 4 + c as usize * 2);
+        assert_eq!(&data[..4], &[w, h, (c >> 8) as u8, c as u8]);
+        let n = mbf::import(&data).unwrap();
+        assert_eq!((n.width, n.height, n.count), (w, h, c));
+        assert_eq!(n.export(false), m.export(false));
+    }
+}
+
+#[test]
+fn zero_mines_and_concatenation() {
+    let empty = MineMap::by_mines(4, 3, &[]).unwrap();
+    assert_eq!(empty.count, 0);
+    assert!(empty.iter().all(|c| c.is_empty()));
+    let data = mbf::export(&empty).unwrap();
+    assert_eq!(data, [4, 3, 0, 0]);
+    assert_eq!(
+        mbf::import(&data).unwrap().export(false),
+        empty.export(false)
+    );
+
+    let a = board(9, 9, 10, 1);
+    let mut all = mbf::export(&a).unwrap();
+    all.extend(&data);
+    all.extend(mbf::export(&a).unwrap());
+    let ls = mbf::import_all(&all).unwrap();
+    assert_eq!(ls.len(), 3);
+    assert_eq!(ls[1].count, 0);
+    assert_eq!(ls[2].export(false), a.export(false));
+}
+
+#[test]
+fn rejects_bad_input() {
+    // 尚未布置地雷
+    assert!(mbf::export(&MineMap::new(10, 9, 9).unwrap()).is_err());
+    // 太短、坐标不足、多余字节
+    assert!(mbf::import(&[9, 9, 0]).is_err());
+    assert!(mbf::import(&[9, 9, 0, 2, 1, 1]).is_err());
+    assert!(mbf::import(&[9, 9, 0, 1, 1, 1, 0]).is_err());
+    // 越界、重复
+    assert!(mbf::import(&[9, 9, 0, 1, 9, 0]).is_err());
+    assert!(mbf::import(&[9, 9, 0, 2, 1, 1, 1, 1]).is_err());
+    // 区域太小、地雷占满区域
+    assert!(mbf::import(&[1, 9, 0, 0]).is_err());
+    assert!(mbf::import(&[2, 2, 0, 4, 0, 0, 1, 0, 0, 1, 1, 1]).is_err());
+    assert!(MineMap::by_mines(2, 2, &[0, 1, 2, 3]).is_err());
+    let Err(e) = mbf::import_all(&[2, 2, 0, 0, 2, 2, 0, 1]) else {
+        panic!("应当失败");
+    };
+    assert!(e.starts_with("第2个布局"), "{e}");
+}
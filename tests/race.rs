@@ -0,0 +1,158 @@
+use std::{
+    net::TcpStream,
+    process::{Command, Stdio},
+    time::Duration,
+};
+
+use mines::{
+    game::{Action, Game, Status},
+    location::Loc,
+    mmap::MineMap,
+    wire::{self, Msg, Standing},
+};
+
+mod common;
+
+use common::Server;
+
+fn start(seed: u64) -> Server {
+    common::spawn(
+        Command::new(env!("CARGO_BIN_EXE_race_server"))
+            .args([
+                "--port", "0", "--width", "5", "--height", "5", "--count", "3",
+            ])
+            .args(["--seed", &seed.to_string()])
+            .stderr(Stdio::null()),
+    )
+}
+
+fn join(s: &Server, name: &str) -> TcpStream {
+    let mut c = TcpStream::connect(("127.0.0.1", s.1)).unwrap();
+    let name = name.to_string();
+    wire::send(&mut c, &Msg::Hello { name }).unwrap();
+    // 等到分配编号再返回，保证编号与加入顺序一致
+    until(&mut c, |m| matches!(m, Msg::Welcome { .. }));
+    c
+}
+
+/// 读取消息直到满足条件
+fn until(c: &mut TcpStream, f: impl Fn(&Msg) -> bool) -> Msg {
+    loop {
+        let msg = wire::recv(c).unwrap();
+        if f(&msg) {
+            return msg;
+        }
+    }
+}
+
+#[test]
+fn frame_roundtrip() {
+    let ls = [
+        Msg::Act(Action::Chord(Loc(3, 4))),
+        Msg::Start {
+            width: 9,
+            height: 9,
+            count: 10,
+            start: Loc(4, 4),
+            players: vec![(0, "甲".to_string()), (1, "b".to_string())],
+        },
+        Msg::End {
+            standings: vec![Standing {
+                id: 1,
+                status: Status::Won,
+                ms: 1234,
+                percent: 100,
+            }],
+        },
+    ];
+    for m in ls {
+        let frame = m.encode();
+        assert_eq!(wire::recv(&mut &frame[..]).unwrap(), m);
+    }
+    // 版本不符
+    let mut frame = Msg::Left { id: 0 }.encode();
+    frame[4] += 1;
+    assert!(wire::recv(&mut &frame[..]).is_err());
+}
+
+#[test]
+fn race_on_identical_boards() {
+    let seed = 3;
+    let s = start(seed);
+    let (mut a, mut b) = (join(&s, "a"), join(&s, "b"));
+    for c in [&mut a, &mut b] {
+        until(c, |m| matches!(m, Msg::Start { .. }));
+    }
+
+    // 与服务端相同的方式生成布局
+    let mut layout = MineMap::new(3, 5, 5).unwrap();
+    layout.new_game_by_seed(Some(Loc(2, 2)), seed);
+    let cells: Vec<_> = layout.iter().enumerate().collect();
+    let mine = cells.iter().find(|(_, c)| c.is_mine()).unwrap().0;
+
+    wire::send(
+        &mut b,
+        &Msg::Act(Action::Reveal(Loc::from(mine % 5, mine / 5))),
+    )
+    .unwrap();
+    let dead = until(&mut a, |m| matches!(m, Msg::Dead { .. }));
+    assert!(matches!(dead, Msg::Dead { id: 1, .. }));
+    // 对手尚未结束，踩雷后只显示踩中的地雷
+    let lost = |m: &Msg| {
+        matches!(
+            m,
+            Msg::View {
+                status: Status::Lost,
+                ..
+            }
+        )
+    };
+    let Msg::View { cells: view, .. } = until(&mut b, lost) else {
+        unreachable!()
+    };
+    let shown: Vec<usize> = (0..view.len()).filter(|&i| view[i] & 0x1f > 8).collect();
+    assert_eq!(shown, vec![mine]);
+
+    // 本地同步对局，获胜后不再发送：服务端退出时若仍有未读数据，连接会被重置
+    layout.reveal(2, 2);
+    let mut local = Game::by_map(layout);
+    for (i, _) in cells.iter().filter(|(_, c)| !c.is_mine()) {
+        let action = Action::Reveal(Loc::from(i % 5, i / 5));
+        if local.is_over() || local.act(action) == 0 {
+            continue;
+        }
+        wire::send(&mut a, &Msg::Act(action)).unwrap();
+    }
+    assert_eq!(local.status(), Status::Won);
+    let finish = until(&mut b, |m| matches!(m, Msg::Finish { .. }));
+    assert!(matches!(finish, Msg::Finish { id: 0, .. }));
+    // 全部结束后公布完整布局
+    let Msg::View { cells: view, .. } = until(&mut b, |m| matches!(m, Msg::View { .. })) else {
+        unreachable!()
+    };
+    assert_eq!(view.iter().filter(|&&v| v & 0x1f > 8).count(), 3);
+    let Msg::End { standings } = until(&mut a, |m| matches!(m, Msg::End { .. })) else {
+        unreachable!()
+    };
+    let ids: Vec<u8> = standings.iter().map(|s| s.id).collect();
+    assert_eq!(ids, vec![0, 1]);
+    assert_eq!(standings[0].status, Status::Won);
+    assert_eq!(standings[1].status, Status::Lost);
+}
+
+#[test]
+fn idle_connection_does_not_block_join() {
+    let s = start(1);
+    // 不发送Hello的连接
+    let _idle = TcpStream::connect(("127.0.0.1", s.1)).unwrap();
+    let mut a = TcpStream::connect(("127.0.0.1", s.1)).unwrap();
+    // 远小于服务端等待Hello的10秒
+    a.set_read_timeout(Some(Duration::from_secs(3))).unwrap();
+    let name = "a".to_string();
+    wire::send(&mut a, &Msg::Hello { name }).unwrap();
+    assert_eq!(wire::recv(&mut a).unwrap(), Msg::Welcome { id: 0, need: 2 });
+    let mut b = join(&s, "b");
+    for c in [&mut a, &mut b] {
+        until(c, |m| matches!(m, Msg::Start { .. }));
+    }
+}
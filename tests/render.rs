@@ -0,0 +1,53 @@
+use mines::{
+    mmap::MineMap,
+    render::{Ansi, Html, Plain, Renderer, Theme, Unicode},
+};
+
+/// 中心为8的3x3
+fn full() -> MineMap {
+    "* * *\n* 8 *\n* * *".parse().unwrap()
+}
+
+#[test]
+fn plain_shows_eight() {
+    let map = full();
+    assert_eq!(map.format_stat_str(), " · · ·\n · 8 ·\n · · ·\n");
+    assert_eq!(map.format_str(), " - - -\n - 8 -\n - - -\n");
+}
+
+#[test]
+fn labels_align_with_coordinates() {
+    let mut map: MineMap = ". . . . . . . . . . . *\n. . . . . . . . . . . ."
+        .parse()
+        .unwrap();
+    map.switch_flag(11, 0);
+    let s = Plain {
+        labels: true,
+        ..Default::default()
+    }
+    .render(&map);
+    let lines: Vec<&str> = s.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].ends_with(" 10 11"));
+    assert!(lines[1].starts_with("0  .  ."));
+    assert!(lines[1].ends_with("  F"));
+    assert!(lines.iter().all(|l| l.len() == lines[0].len()));
+}
+
+#[test]
+fn backends() {
+    let map = full();
+    let s = Ansi::default().render(&map);
+    assert!(s.contains("\x1b[1;38;5;244m8\x1b[0m"));
+    let s = Unicode {
+        theme: Theme::EMOJI,
+        labels: true,
+        layout: true,
+    }
+    .render(&map);
+    assert_eq!(s.lines().count(), 6);
+    assert!(s.contains("│ 💣 ８ 💣 │"));
+    let s = Html::default().render(&map);
+    assert_eq!(s.matches("<td").count(), 9);
+    assert_eq!(s.matches("<tr>").count(), 3);
+}
@@ -0,0 +1,244 @@
+use mines::{
+    game::{Action, Game, Status},
+    location::Loc,
+    mmap::{MineMap, Safety},
+    replay::{
+        avf, mrpl, mvf, rmv,
+        verify::{self, Limits},
+        Replay,
+    },
+};
+
+/// 4x3，地雷位于(0,0)与(3,2)
+const W: u8 = 4;
+const H: u8 = 3;
+
+/// 期望的操作：打开(2,0)，插旗(0,0)，双击(1,1)
+fn expected() -> Vec<Action> {
+    vec![
+        Action::Reveal(Loc(2, 0)),
+        Action::Flag(Loc(0, 0)),
+        Action::Chord(Loc(1, 1)),
+    ]
+}
+
+/// 单元格中心的像素坐标
+fn px(v: u8) -> [u8; 2] {
+    (v as u16 * 16 + 8).to_be_bytes()
+}
+
+fn check(r: &Replay) {
+    let mines: Vec<usize> = r
+        .map
+        .iter()
+        .enumerate()
+        .filter_map(|(i, c)| c.is_mine().then_some(i))
+        .collect();
+    assert_eq!(mines, vec![0, 11]);
+    let actions: Vec<Action> = r.events.iter().map(|e| e.action).collect();
+    assert_eq!(actions, expected());
+    assert!(r.events.windows(2).all(|w| w[0].ms <= w[1].ms));
+    // 重放后打开了除地雷外的全部单元格
+    assert_eq!(r.play().status(), Status::Won);
+}
+
+#[test]
+fn parse_avf() {
+    let mut d = vec![1, 0, 0, 0, 0, 6, W - 1, H - 1, 0, 2];
+    // (row, col)，从1开始
+    d.extend([1, 1, 3, 4]);
+    d.extend(b"player[0|2024.01.01|name]");
+    let mut ev = |kind: u8, x: u8, y: u8, ms: u32| {
+        d.push(kind);
+        d.extend(px(x));
+        d.extend(px(y));
+        d.extend(&ms.to_be_bytes()[1..]);
+    };
+    ev(1, 2, 0, 0);
+    ev(3, 2, 0, 10);
+    ev(5, 2, 0, 80);
+    ev(9, 0, 0, 300);
+    ev(17, 0, 0, 350);
+    ev(1, 1, 1, 500);
+    ev(3, 1, 1, 700);
+    ev(9, 1, 1, 720);
+    ev(5, 1, 1, 800);
+    ev(17, 1, 1, 820);
+    d.push(0);
+    check(&avf::parse(&d).unwrap());
+}
+
+#[test]
+fn parse_avf_preset_mode() {
+    let mut d = vec![1, 0, 0, 0, 0, 3];
+    // 第1行铺满8颗，第2行2颗
+    for col in 1..=8 {
+        d.extend([1, col]);
+    }
+    d.extend([2, 1, 2, 2]);
+    d.extend(b"[]");
+    d.push(0);
+    let r = avf::parse(&d).unwrap();
+    assert_eq!((r.map.width, r.map.height, r.map.count), (8, 8, 10));
+    assert!(r.events.is_empty());
+}
+
+#[test]
+fn parse_rmv() {
+    let mut d = rmv::MAGIC.to_vec();
+    d.extend(1u16.to_be_bytes());
+    let info = b"player: test";
+    d.extend((info.len() as u16).to_be_bytes());
+    d.extend(info);
+    d.extend([W, H, 0, 2]);
+    // (x, y)，从0开始
+    d.extend([0, 0, 3, 2]);
+    let mut ev = |kind: u8, x: u8, y: u8, ms: u32| {
+        d.push(kind);
+        d.extend(&ms.to_be_bytes()[1..]);
+        d.extend(px(x));
+        d.extend(px(y));
+    };
+    ev(2, 2, 0, 10);
+    ev(1, 2, 0, 40);
+    ev(3, 2, 0, 80);
+    ev(4, 0, 0, 300);
+    ev(5, 0, 0, 350);
+    ev(6, 1, 1, 700);
+    ev(7, 1, 1, 800);
+    d.push(0);
+    check(&rmv::parse(&d).unwrap());
+}
+
+#[test]
+fn parse_mvf() {
+    let mut d = mvf::MAGIC.to_vec();
+    d.extend([W, H, 0, 2]);
+    // (x, y)，从1开始
+    d.extend([1, 1, 4, 3]);
+    let mut ev = |ms: u32, buttons: u8, x: u8, y: u8| {
+        d.extend(&ms.to_be_bytes()[1..]);
+        d.push(buttons);
+        d.extend(px(x));
+        d.extend(px(y));
+    };
+    ev(0, 0, 2, 0);
+    ev(10, 1, 2, 0);
+    ev(80, 0, 2, 0);
+    ev(300, 2, 0, 0);
+    ev(350, 0, 0, 0);
+    ev(700, 1, 1, 1);
+    ev(720, 3, 1, 1);
+    ev(800, 2, 1, 1);
+    ev(820, 0, 1, 1);
+    check(&mvf::parse(&d).unwrap());
+}
+
+#[test]
+fn reject_bad_files() {
+    assert!(rmv::parse(b"*rmx\x00\x01").is_err());
+    assert!(mvf::parse(&[0x11, 0x4D, W, H, 0, 1, 0, 1]).is_err());
+    // 地雷坐标越界
+    assert!(avf::parse(&[1, 0, 0, 0, 0, 6, 1, 1, 0, 1, 3, 3, b'[', b']']).is_err());
+    // 数据截断
+    assert!(avf::parse(&[1, 0, 0, 0, 0, 6, 3, 2, 0, 2, 1, 1]).is_err());
+}
+
+/// 基于种子获胜的一局，每步间隔`gap`毫秒
+fn seeded_win(seed: u64, gap: u32) -> Replay {
+    seeded_win_with(seed, gap, Safety::default())
+}
+
+fn seeded_win_with(seed: u64, gap: u32, safety: Safety) -> Replay {
+    let mut game = Game::by_seed(MineMap::new(10, 9, 9).unwrap(), seed).with_safety(safety);
+    game.act(Action::Reveal(Loc(4, 4)));
+    for i in 0..81 {
+        if !game.map().iter().nth(i).unwrap().is_mine() {
+            game.act(Action::Reveal(Loc::from(i % 9, i / 9)));
+        }
+    }
+    assert_eq!(game.status(), Status::Won);
+    let mut r = Replay::from_game(&game);
+    for (i, e) in r.events.iter_mut().enumerate() {
+        e.ms = i as u32 * gap;
+    }
+    r
+}
+
+#[test]
+fn verify_seeded_replay() {
+    let r = seeded_win(5, 300);
+    let data = mrpl::export(&r).unwrap();
+    let report = verify::verify_file(&data, &Limits::default()).unwrap();
+    assert_eq!((report.actions, report.ignored), (r.events.len(), 0));
+    assert_eq!(report.bbbv, r.map.count_3bv());
+    // 损坏的文件
+    let mut bad = data.clone();
+    bad[10] ^= 1;
+    assert!(Replay::load(&bad).is_err());
+}
+
+#[test]
+fn verify_non_default_safety() {
+    let limits = Limits::default();
+    for safety in [Safety::None, Safety::Cell, Safety::Radius(2)] {
+        let r = seeded_win_with(9, 300, safety);
+        assert_eq!(r.safety, safety);
+        let parsed = Replay::load(&mrpl::export(&r).unwrap()).unwrap();
+        assert_eq!(parsed.safety, safety);
+        verify::verify(&parsed, &limits).unwrap();
+        // 声明的策略与生成布局时不同
+        let wrong = Replay {
+            safety: Safety::Opening,
+            ..parsed
+        };
+        assert!(verify::verify(&wrong, &limits).is_err(), "{safety:?}");
+    }
+
+    // 未知版本
+    let mut data = mrpl::export(&seeded_win(5, 300)).unwrap();
+    data[4] = 2;
+    data.truncate(data.len() - 4);
+    let crc = mines::save::crc32(&data);
+    data.extend(crc.to_be_bytes());
+    assert!(Replay::load(&data).is_err());
+}
+
+#[test]
+fn reject_cheating_replays() {
+    let limits = Limits::default();
+    let r = seeded_win(5, 300);
+    // 种子与布局不符
+    let wrong = Replay {
+        seed: Some(6),
+        ..seeded_win(5, 300)
+    };
+    assert!(verify::verify(&wrong, &limits).is_err());
+    // 间隔过短
+    assert!(verify::verify(&seeded_win(5, 5), &limits).is_err());
+    // 连续操作过快：每秒10次，上限为5次
+    let burst = Limits {
+        max_in_window: 5,
+        ..limits
+    };
+    assert!(verify::verify(&seeded_win(5, 100), &burst).is_err());
+    assert!(verify::verify(&seeded_win(5, 250), &burst).is_ok());
+    // 时间倒流
+    let mut back = seeded_win(5, 300);
+    back.events[2].ms = 0;
+    assert!(verify::verify(&back, &limits).is_err());
+    // 未获胜
+    let mut lost = seeded_win(5, 300);
+    lost.events.pop();
+    assert!(verify::verify(&lost, &limits).is_err());
+    // 无效操作只计数：重复打开同一单元格
+    let mut noop = seeded_win(5, 300);
+    let e = noop.events[0];
+    noop.events.insert(1, e);
+    for (i, e) in noop.events.iter_mut().enumerate() {
+        e.ms = i as u32 * 300;
+    }
+    let report = verify::verify(&noop, &limits).unwrap();
+    assert_eq!((report.actions, report.ignored), (r.events.len(), 1));
+    assert!(verify::verify(&r, &limits).is_ok());
+}
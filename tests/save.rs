@@ -0,0 +1,141 @@
+use mines::{
+    game::{Action, Game, Status},
+    location::Loc,
+    mmap::{MineMap, Safety},
+    save::{self, crc32, Kind},
+};
+
+/// 重新计算末尾的CRC
+fn reseal(mut data: Vec<u8>) -> Vec<u8> {
+    data.truncate(data.len() - 4);
+    let crc = crc32(&data);
+    data.extend(crc.to_le_bytes());
+    data
+}
+
+fn playing() -> Game {
+    let mut g = Game::by_seed(MineMap::new(10, 9, 9).unwrap(), 7).with_safety(Safety::Radius(2));
+    g.act(Action::Reveal(Loc(4, 4)));
+    let mine = g.map().iter().position(|c| c.is_mine()).unwrap();
+    g.act(Action::Flag(Loc((mine % 9) as u8, (mine / 9) as u8)));
+    g
+}
+
+#[test]
+fn crc32_check_value() {
+    assert_eq!(crc32(b""), 0);
+    assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+}
+
+#[test]
+fn progress_round_trip() {
+    let g = playing();
+    assert_eq!(g.status(), Status::Playing);
+    let (kind, h) = save::import(&save::export_game(&g)).unwrap();
+    assert_eq!(kind, Kind::Progress);
+    assert_eq!(h.map().export(true), g.map().export(true));
+    assert_eq!(h.status(), Status::Playing);
+    assert_eq!(h.seed(), Some(7));
+    assert_eq!(h.safety(), Safety::Radius(2));
+    assert_eq!(h.actions(), 2);
+    assert_eq!(h.history(), g.history());
+}
+
+#[test]
+fn ready_and_layout_round_trip() {
+    // 尚未开局：保留尺寸、种子与保护策略
+    let g = Game::by_seed(MineMap::new(10, 9, 9).unwrap(), 3).with_safety(Safety::Cell);
+    let (kind, mut h) = save::import(&save::export_game(&g)).unwrap();
+    assert_eq!(
+        (kind, h.status(), h.seed()),
+        (Kind::Progress, Status::Ready, Some(3))
+    );
+    assert_eq!(h.safety(), Safety::Cell);
+    let mut g = g;
+    g.act(Action::Reveal(Loc(0, 0)));
+    h.act(Action::Reveal(Loc(0, 0)));
+    assert_eq!(h.map().export(false), g.map().export(false));
+
+    // 仅布局：不含进度与操作记录
+    let (kind, l) = save::import(&save::export_layout(g.map()).unwrap()).unwrap();
+    assert_eq!(kind, Kind::Layout);
+    assert_eq!(l.map().export(false), g.map().export(false));
+    assert!(l.map().iter().all(|c| !c.is_reveal()));
+    assert!(l.history().is_empty());
+
+    // 尚未布置地雷
+    assert!(save::export_layout(&MineMap::new(10, 9, 9).unwrap()).is_err());
+}
+
+#[test]
+fn reads_newer_version() {
+    // 新版本在头部末尾追加的字段被跳过
+    let g = playing();
+    let data = save::export_game(&g);
+    let mut newer = data[..7 + 31].to_vec();
+    newer[4] = 2;
+    newer[5..7].copy_from_slice(&35u16.to_le_bytes());
+    newer.extend([1, 2, 3, 4]);
+    newer.extend(&data[7 + 31..]);
+    let (kind, h) = save::import(&reseal(newer)).unwrap();
+    assert_eq!(kind, Kind::Progress);
+    assert_eq!(h.map().export(true), g.map().export(true));
+    assert_eq!(h.safety(), Safety::Radius(2));
+    assert_eq!(h.history(), g.history());
+}
+
+#[test]
+fn legacy_migration() {
+    let g = playing();
+    let legacy = g.map().export(true);
+    let (kind, h) = save::import(&legacy).unwrap();
+    assert_eq!(kind, Kind::Progress);
+    assert_eq!(h.map().export(true), legacy);
+
+    let migrated = save::migrate(&legacy).unwrap();
+    assert!(migrated.starts_with(save::MAGIC));
+    let (kind, m) = save::import(&migrated).unwrap();
+    assert_eq!(kind, Kind::Progress);
+    assert_eq!(m.map().export(true), legacy);
+
+    // 没有打开、插旗标记的旧存档为仅布局
+    let layout = g.map().export(false);
+    assert_eq!(save::import(&layout).unwrap().0, Kind::Layout);
+}
+
+#[test]
+fn rejects_corrupt_input() {
+    let data = save::export_game(&playing());
+    // 校验失败
+    let mut bad = data.clone();
+    bad[40] ^= 1;
+    assert!(save::import(&bad).is_err());
+    // 截断
+    assert!(save::import(&data[..data.len() - 1]).is_err());
+    assert!(save::import(&data[..10]).is_err());
+    // 无效版本
+    let mut bad = data.clone();
+    bad[4] = 0;
+    assert!(save::import(&reseal(bad)).is_err());
+    // 头部短于当前版本
+    let mut bad = data.clone();
+    bad[5] = 25;
+    assert!(save::import(&reseal(bad)).is_err());
+    // 未知类型、状态与保护策略
+    for (i, v) in [(7, 2), (12, 4), (32, 9)] {
+        let mut bad = data.clone();
+        bad[i] = v;
+        assert!(save::import(&reseal(bad)).is_err());
+    }
+    // 操作记录条数与数据不符
+    let mut bad = data.clone();
+    bad[34] += 1;
+    assert!(save::import(&reseal(bad)).is_err());
+    // 宽高与数据长度不符
+    assert!(save::import(&[10, 10, 0, 0, 0, 0]).is_err());
+    assert!(save::migrate(&[3, 3, 0, 0, 0, 0]).is_err());
+    assert!(MineMap::by(vec![1, 4, 0, 0, 0, 0], false).is_err());
+    let mut bad = data.clone();
+    bad[8] = 10;
+    assert!(save::import(&reseal(bad)).is_err());
+}
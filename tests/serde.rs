@@ -0,0 +1,92 @@
+#![cfg(feature = "serde")]
+
+use mines::{
+    cell::Cell,
+    game::{Action, Game, Status},
+    location::Loc,
+    mmap::{MineMap, Safety},
+    replay::Replay,
+};
+use serde_json::{from_str, json, to_string, to_value, Value};
+
+fn playing() -> Game {
+    let mut g = Game::by_seed(MineMap::new(10, 9, 9).unwrap(), 11).with_safety(Safety::Radius(2));
+    g.act(Action::Reveal(Loc(4, 4)));
+    let mine = g.map().iter().position(|c| c.is_mine()).unwrap();
+    g.act(Action::Flag(Loc((mine % 9) as u8, (mine / 9) as u8)));
+    g
+}
+
+#[test]
+fn cell_and_loc() {
+    for v in [0x00, 0x03, 0x09, 0x0b, 0x80 | 2, 0x40 | 9, 0x80 | 0x0c] {
+        let c = Cell(v);
+        let s = to_string(&c).unwrap();
+        assert_eq!(from_str::<Cell>(&s).unwrap().0, v, "{s}");
+    }
+    assert_eq!(
+        to_value(Cell(0x80 | 0x0a)).unwrap(),
+        json!({"revealed": true, "flagged": false, "mine": true, "warn": 1})
+    );
+    assert!(
+        from_str::<Cell>(r#"{"revealed":false,"flagged":false,"mine":false,"warn":9}"#).is_err()
+    );
+
+    assert_eq!(to_value(Loc(3, 7)).unwrap(), json!({"x": 3, "y": 7}));
+    assert_eq!(from_str::<Loc>(r#"{"x":3,"y":7}"#).unwrap(), Loc(3, 7));
+}
+
+#[test]
+fn mine_map() {
+    let g = playing();
+    let m = from_str::<MineMap>(&to_string(g.map()).unwrap()).unwrap();
+    assert_eq!((m.width, m.height, m.count), (9, 9, 10));
+    assert_eq!(m.export(true), g.map().export(true));
+
+    // 尚未布置地雷
+    let m = from_str::<MineMap>(&to_string(&MineMap::new(10, 9, 9).unwrap()).unwrap()).unwrap();
+    assert_eq!(m.count, 10);
+    assert!(m.iter().all(|c| !c.is_mine()));
+    // 没有地雷的布局
+    let empty = MineMap::by_mines(3, 2, &[]).unwrap();
+    let m = from_str::<MineMap>(&to_string(&empty).unwrap()).unwrap();
+    assert_eq!(m.count, 0);
+
+    // 尺寸、单元格数量、地雷数不符
+    let mut v = to_value(g.map()).unwrap();
+    let cells = v["cells"].clone();
+    v["width"] = json!(1);
+    v["height"] = json!(81);
+    assert!(serde_json::from_value::<MineMap>(v.clone()).is_err());
+    v["width"] = json!(9);
+    v["height"] = json!(8);
+    assert!(serde_json::from_value::<MineMap>(v.clone()).is_err());
+    v["height"] = json!(9);
+    v["count"] = json!(11);
+    assert!(serde_json::from_value::<MineMap>(v.clone()).is_err());
+    v["count"] = json!(10);
+    v["cells"] = Value::Array(cells.as_array().unwrap()[..80].to_vec());
+    assert!(serde_json::from_value::<MineMap>(v).is_err());
+}
+
+#[test]
+fn game_and_replay() {
+    let g = playing();
+    let h: Game = from_str(&to_string(&g).unwrap()).unwrap();
+    assert_eq!(h.map().export(true), g.map().export(true));
+    assert_eq!(h.status(), Status::Playing);
+    assert_eq!((h.seed(), h.safety()), (Some(11), Safety::Radius(2)));
+    assert_eq!((h.actions(), h.history()), (g.actions(), g.history()));
+
+    // 缺少safety时为默认策略
+    let mut v = to_value(&g).unwrap();
+    v.as_object_mut().unwrap().remove("safety");
+    let h: Game = serde_json::from_value(v).unwrap();
+    assert_eq!(h.safety(), Safety::Opening);
+
+    let r = Replay::from_game(&g);
+    let s: Replay = from_str(&to_string(&r).unwrap()).unwrap();
+    assert_eq!(s.map.export(false), r.map.export(false));
+    assert_eq!((s.seed, &s.events), (r.seed, &r.events));
+    assert_eq!(s.play().map().export(true), g.map().export(true));
+}
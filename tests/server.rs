@@ -0,0 +1,93 @@
+use std::{
+    io::{Read, Write},
+    net::TcpStream,
+    process::Command,
+};
+
+use mines::{
+    game::{Action, Game},
+    location::Loc,
+    mmap::MineMap,
+};
+
+mod common;
+
+use common::Server;
+
+fn start() -> Server {
+    common::spawn(Command::new(env!("CARGO_BIN_EXE_server")).arg("0"))
+}
+
+fn request(s: &Server, method: &str, path: &str, body: &str) -> (u16, String) {
+    let mut stream = TcpStream::connect(("127.0.0.1", s.1)).unwrap();
+    write!(
+        stream,
+        "{method} {path} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{body}",
+        body.len()
+    )
+    .unwrap();
+    let mut res = String::new();
+    stream.read_to_string(&mut res).unwrap();
+    let status = res[9..12].parse().unwrap();
+    let body = res.split_once("\r\n\r\n").unwrap().1.to_string();
+    (status, body)
+}
+
+/// 从JSON中取出字符串字段
+fn field<'a>(json: &'a str, key: &str) -> &'a str {
+    let start = json.find(&format!("\"{key}\":\"")).unwrap() + key.len() + 4;
+    &json[start..start + json[start..].find('"').unwrap()]
+}
+
+#[test]
+fn play_until_won() {
+    let s = start();
+    let body = r#"{"width": 9, "height": 9, "mines": 10, "seed": 42}"#;
+    let (code, res) = request(&s, "POST", "/games", body);
+    assert_eq!(code, 201);
+    let id = field(&res, "id").to_string();
+
+    // 相同种子在本地得到相同布局
+    let mut local = Game::by_seed(MineMap::new(10, 9, 9).unwrap(), 42);
+    local.act(Action::Reveal(Loc(4, 4)));
+    let (code, res) = request(&s, "POST", &format!("/games/{id}/reveal?x=4&y=4"), "");
+    assert_eq!(code, 200);
+    assert!(res.contains("\"status\":\"playing\""));
+    // 进行中不泄露地雷与种子
+    assert!(!res.contains('*') && !res.contains("seed"));
+
+    let w = local.map().width as usize;
+    let safe: Vec<usize> = local
+        .map()
+        .iter()
+        .enumerate()
+        .filter_map(|(i, c)| (!c.is_mine()).then_some(i))
+        .collect();
+    for i in safe {
+        let body = format!(r#"{{"x": {}, "y": {}}}"#, i % w, i / w);
+        request(&s, "POST", &format!("/games/{id}/reveal"), &body);
+    }
+    let (_, res) = request(&s, "GET", &format!("/games/{id}"), "");
+    assert_eq!(field(&res, "status"), "won");
+    assert!(res.contains("\"seed\":42"));
+    assert_eq!(res.matches('*').count(), 10);
+    let (_, res) = request(&s, "GET", &format!("/games/{id}/stats"), "");
+    assert!(res.contains("\"3bv\":"));
+}
+
+#[test]
+fn reject_bad_requests() {
+    let s = start();
+    assert_eq!(request(&s, "GET", "/games/nope", "").0, 404);
+    assert_eq!(request(&s, "POST", "/games", r#"{"width": 9}"#).0, 400);
+    assert_eq!(
+        request(&s, "POST", "/games?width=2&height=2&mines=9", "").0,
+        400
+    );
+    let (_, res) = request(&s, "POST", "/games?width=3&height=3&mines=1", "");
+    let id = field(&res, "id").to_string();
+    let path = format!("/games/{id}/reveal?x=3&y=0");
+    assert_eq!(request(&s, "POST", &path, "").0, 400);
+    assert_eq!(request(&s, "DELETE", &format!("/games/{id}"), "").0, 204);
+    assert_eq!(request(&s, "GET", &format!("/games/{id}"), "").0, 404);
+}
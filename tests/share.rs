@@ -0,0 +1,74 @@
+use mines::{
+    location::Loc,
+    mmap::MineMap,
+    share::{decode_code, encode_code, pack, unpack},
+};
+
+fn boards() -> Vec<MineMap> {
+    // 不同尺寸使base64末尾的字符数各不相同
+    [(9, 9, 10), (16, 16, 40), (30, 16, 99), (5, 3, 4), (2, 2, 1)]
+        .into_iter()
+        .enumerate()
+        .map(|(i, (w, h, c))| {
+            let mut m = MineMap::new(c, w, h).unwrap();
+            m.new_game_by_seed(None, i as u64);
+            m
+        })
+        .collect()
+}
+
+#[test]
+fn pack_round_trip() {
+    for m in boards() {
+        let p = pack(&m).unwrap();
+        assert_eq!(p.len(), 2 + m.map.len().div_ceil(8));
+        assert_eq!(unpack(&p).unwrap().export(false), m.export(false));
+    }
+    assert!(unpack(&[3, 3]).is_err());
+    assert!(unpack(&[3, 3, 0]).is_err());
+    // 尚未布置地雷
+    let m = MineMap::new(10, 9, 9).unwrap();
+    assert!(pack(&m).is_err());
+    assert!(encode_code(&m, None).is_err());
+}
+
+#[test]
+fn code_round_trip() {
+    for m in boards() {
+        for first in [None, Some(Loc(0, 0)), Some(Loc(m.width - 1, m.height - 1))] {
+            let code = encode_code(&m, first).unwrap();
+            assert!(code
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_'));
+            let (n, f) = decode_code(&code).unwrap();
+            assert_eq!(n.export(false), m.export(false));
+            assert_eq!(f, first);
+        }
+    }
+    // 首尾空白被忽略
+    let m = &boards()[0];
+    let code = encode_code(m, None).unwrap();
+    assert!(decode_code(&format!(" {code}\n")).is_ok());
+}
+
+#[test]
+fn rejects_bad_codes() {
+    for m in boards() {
+        let code = encode_code(&m, Some(Loc(1, 1))).unwrap();
+        // 截断
+        for n in [0, 4, code.len() / 2, code.len() - 1] {
+            assert!(decode_code(&code[..n]).is_err(), "截断到{n}");
+        }
+        // 逐个字符篡改，包括末尾的填充位
+        for i in 0..code.len() {
+            let mut b = code.clone().into_bytes();
+            b[i] = if b[i] == b'A' { b'B' } else { b'A' };
+            let bad = String::from_utf8(b).unwrap();
+            assert!(decode_code(&bad).is_err(), "{code}篡改第{i}个字符");
+        }
+        // 无效字符、多余字符
+        assert!(decode_code(&format!("{code}=")).is_err());
+        assert!(decode_code(&format!("{code}A")).is_err());
+        assert!(decode_code(&format!("+{}", &code[1..])).is_err());
+    }
+}
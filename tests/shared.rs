@@ -0,0 +1,89 @@
+use std::thread;
+
+use mines::{
+    game::{Action, Status},
+    location::Loc,
+    mmap::MineMap,
+    shared::{Mode, Scoring, Shared, Table},
+};
+
+/// 3x2，地雷位于(1,0)，其余单元格均为1
+fn board() -> MineMap {
+    ". * .\n. . .".parse().unwrap()
+}
+
+#[test]
+fn versus_turns_and_scores() {
+    let mut s = Shared::by_map(board(), Mode::Versus(Scoring::default()), 2).unwrap();
+    assert_eq!(s.turn(), Some(0));
+    let r = s.act(0, Action::Reveal(Loc(0, 0))).unwrap();
+    assert_eq!((r.opened, r.score, r.turn), (1, 1, Some(1)));
+    // 未轮到
+    assert!(s.act(0, Action::Reveal(Loc(1, 1))).is_err());
+    // 插旗不换手，无效操作也不换手
+    s.act(1, Action::Flag(Loc(2, 0))).unwrap();
+    let r = s.act(1, Action::Reveal(Loc(0, 0))).unwrap();
+    assert_eq!((r.opened, r.turn), (0, Some(1)));
+    // 踩雷扣分，对局继续
+    let r = s.act(1, Action::Reveal(Loc(1, 0))).unwrap();
+    assert_eq!((r.mines, r.score, r.status), (1, -10, Status::Playing));
+    s.act(0, Action::Reveal(Loc(0, 1))).unwrap();
+    s.act(1, Action::Flag(Loc(2, 0))).unwrap();
+    s.act(1, Action::Reveal(Loc(1, 1))).unwrap();
+    s.act(0, Action::Reveal(Loc(2, 1))).unwrap();
+    let r = s.act(1, Action::Reveal(Loc(2, 0))).unwrap();
+    assert_eq!((r.status, r.turn), (Status::Won, None));
+    assert_eq!(s.scores(), &[3, -8]);
+    assert_eq!(s.leaders(), vec![0]);
+    assert_eq!(s.owner(Loc(1, 0)), Some(1));
+    assert_eq!(s.owner(Loc(2, 1)), Some(0));
+    assert_eq!(s.history().len(), 8);
+}
+
+#[test]
+fn coop_mine_ends_game() {
+    let mut s = Shared::by_map(board(), Mode::Coop, 3).unwrap();
+    assert_eq!(s.turn(), None);
+    s.act(2, Action::Reveal(Loc(2, 1))).unwrap();
+    s.act(0, Action::Reveal(Loc(0, 0))).unwrap();
+    let r = s.act(1, Action::Reveal(Loc(1, 0))).unwrap();
+    assert_eq!(r.status, Status::Lost);
+    assert!(s.act(0, Action::Reveal(Loc(1, 1))).is_err());
+    assert_eq!(s.scores(), &[1, 0, 1]);
+}
+
+#[test]
+fn concurrent_coop() {
+    let map = MineMap::new(40, 30, 16).unwrap();
+    let table = Table::new(Shared::by_seed(map, Mode::Coop, 4, 3).unwrap());
+    table.act(0, Action::Reveal(Loc(15, 8))).unwrap();
+    let safe: Vec<Loc> = {
+        let s = table.lock();
+        let w = s.map().width as usize;
+        s.map()
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| !c.is_mine())
+            .map(|(i, _)| Loc::from(i % w, i / w))
+            .collect()
+    };
+    thread::scope(|sc| {
+        for p in 0..4u8 {
+            let (table, safe) = (&table, &safe);
+            sc.spawn(move || {
+                // 各线程以不同顺序打开全部安全单元格
+                for k in 0..safe.len() {
+                    let _ = table.act(
+                        p,
+                        Action::Reveal(safe[(k * 7 + p as usize * 97) % safe.len()]),
+                    );
+                }
+            });
+        }
+    });
+    let s = table.into_inner();
+    assert_eq!(s.status(), Status::Won);
+    let owned = s.owners().iter().filter(|o| o.is_some()).count();
+    assert_eq!(owned, safe.len());
+    assert_eq!(s.scores().iter().sum::<i32>(), safe.len() as i32);
+}
@@ -0,0 +1,102 @@
+use mines::{cell::Cell, mmap::MineMap, solver::probabilities};
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// 周围一圈的下标
+fn around(i: usize, w: usize, h: usize) -> Vec<usize> {
+    let (x, y) = (i % w, i / w);
+    let mut out = Vec::new();
+    for ay in y.saturating_sub(1)..=(y + 1).min(h - 1) {
+        for ax in x.saturating_sub(1)..=(x + 1).min(w - 1) {
+            if (ax, ay) != (x, y) {
+                out.push(ay * w + ax);
+            }
+        }
+    }
+    out
+}
+
+/// 枚举未知单元格的全部布局，统计与数字及总雷数相符的布局中每个单元格为雷的比例
+fn brute(w: usize, h: usize, count: usize, view: &[u8]) -> Vec<f64> {
+    let hidden: Vec<usize> = (0..view.len())
+        .filter(|&i| !Cell(view[i]).is_reveal())
+        .collect();
+    let numbers: Vec<(usize, usize)> = (0..view.len())
+        .filter(|&i| Cell(view[i]).is_reveal())
+        .map(|i| (i, Cell(view[i]).get_warn() as usize))
+        .collect();
+    let (mut total, mut hits) = (0.0, vec![0.0; view.len()]);
+    let mut mine = vec![false; view.len()];
+    for mask in 0u32..1 << hidden.len() {
+        if mask.count_ones() as usize != count {
+            continue;
+        }
+        for (k, &i) in hidden.iter().enumerate() {
+            mine[i] = mask >> k & 1 == 1;
+        }
+        if numbers
+            .iter()
+            .all(|&(i, n)| around(i, w, h).iter().filter(|&&a| mine[a]).count() == n)
+        {
+            total += 1.0;
+            for &i in &hidden {
+                if mine[i] {
+                    hits[i] += 1.0;
+                }
+            }
+        }
+    }
+    hits.iter().map(|v| v / total).collect()
+}
+
+fn check(w: u8, h: u8, count: u16, view: &[u8]) -> Vec<f64> {
+    let p = probabilities(w, h, count, view).unwrap();
+    let b = brute(w as usize, h as usize, count as usize, view);
+    for i in 0..view.len() {
+        assert!(
+            (p[i] - b[i]).abs() < 1e-9,
+            "{w}x{h}/{count} cell {i}: solver {} brute {}\n{view:?}",
+            p[i],
+            b[i],
+        );
+    }
+    p
+}
+
+#[test]
+fn matches_brute_force() {
+    let mut rng = ChaCha8Rng::seed_from_u64(42);
+    for (w, h, c) in [(4, 3, 3), (5, 4, 5), (4, 4, 6), (6, 3, 4)] {
+        let size = w as usize * h as usize;
+        for _ in 0..40 {
+            let mut cells: Vec<usize> = (0..size).collect();
+            cells.shuffle(&mut rng);
+            let mut map = MineMap::by_mines(w, h, &cells[..c]).unwrap();
+            // 随机打开若干安全单元格
+            let opens = rng.gen_range(1..=(size - c) / 2);
+            for &i in cells[c..].iter().take(opens) {
+                map.reveal(i % w as usize, i / w as usize);
+            }
+            check(w, h, c as u16, &map.view());
+        }
+    }
+}
+
+#[test]
+fn global_count_constraint() {
+    // 4x3，只打开(1,1)且为1，周围8格中恰有1颗雷，最右一列不受数字约束：
+    //   ? ? ? ?
+    //   ? 1 ? ?
+    //   ? ? ? ?
+    let mut view = vec![0u8; 12];
+    view[5] = 0x80 | 1;
+    let p = check(4, 3, 1, &view);
+    assert!((p[0] - 0.125).abs() < 1e-12);
+    assert_eq!((p[3], p[7], p[11]), (0.0, 0.0, 0.0));
+    // 总雷数为2时，第二颗雷只能在最右一列
+    let p = check(4, 3, 2, &view);
+    assert!((p[0] - 0.125).abs() < 1e-12);
+    assert!((p[7] - 1.0 / 3.0).abs() < 1e-12);
+    // 总雷数超出所有可能时无解
+    assert!(probabilities(4, 3, 5, &view).is_none());
+}
@@ -0,0 +1,80 @@
+use mines::{
+    difficulty::Difficulty,
+    game::{Action, Game},
+    location::Loc,
+    stats::{Finish, Store},
+};
+
+fn finish(won: bool, ms: u32) -> Finish {
+    Finish {
+        width: 9,
+        height: 9,
+        count: 10,
+        won,
+        ms,
+        bbbv: 20,
+    }
+}
+
+#[test]
+fn record_and_query() {
+    let mut s = Store::new();
+    for (won, ms) in [
+        (true, 12_300),
+        (true, 9_800),
+        (false, 3_000),
+        (true, 15_100),
+    ] {
+        s.record("alice", &finish(won, ms));
+    }
+    s.record("bob", &finish(true, 11_000));
+
+    assert_eq!(finish(true, 0).difficulty(), Difficulty::Beginner);
+    let r = s.get("alice", Difficulty::Beginner).unwrap();
+    assert_eq!((r.played, r.won, r.streak, r.best_streak), (4, 3, 1, 2));
+    assert_eq!(r.best_ms, Some(9_800));
+    assert_eq!(r.best_bbbv_per_sec, Some(20.0 * 1000.0 / 9_800.0));
+    assert_eq!(r.histogram(5), vec![(5, 1), (10, 1), (15, 1)]);
+    assert_eq!(r.histogram(10), vec![(0, 1), (10, 2)]);
+    assert_eq!(
+        s.leaderboard(Difficulty::Beginner),
+        vec![("alice", 9_800), ("bob", 11_000)]
+    );
+    assert_eq!(s.profiles().collect::<Vec<_>>(), ["alice", "bob"]);
+    assert!(s.get("alice", Difficulty::Intermediate).is_none());
+    let ds: Vec<_> = s.difficulties("alice").map(|(d, _)| d).collect();
+    assert_eq!(ds, [Difficulty::Beginner]);
+}
+
+#[test]
+fn record_finished_game_only() {
+    let mut game = Game::by_map(". * .\n. . .".parse().unwrap());
+    let mut s = Store::new();
+    assert!(!s.record_game("p", &game));
+    game.act(Action::Reveal(Loc(1, 0)));
+    assert!(s.record_game("p", &game));
+    let r = s.get("p", Difficulty::of(3, 2, 1)).unwrap();
+    assert_eq!((r.played, r.won, r.best_ms), (1, 0, None));
+}
+
+#[test]
+fn save_and_load() {
+    let dir = std::env::temp_dir().join(format!("mines-stats-{}", std::process::id()));
+    let path = dir.join("nested").join("stats.txt");
+    assert_eq!(Store::load(&path).unwrap(), Store::new());
+
+    let mut s = Store::new();
+    s.record("tab\tname", &finish(true, 7_250));
+    s.record("tab\tname", &finish(false, 1_000));
+    s.save(&path).unwrap();
+    let t = Store::load(&path).unwrap();
+    assert_eq!(s, t);
+    assert!(t.get("tab name", Difficulty::Beginner).is_some());
+    // 文件中的难度按尺寸记录
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(text.contains("\t9x9/10\t"), "{text}");
+
+    std::fs::write(&path, "mines-stats 99\n").unwrap();
+    assert!(Store::load(&path).is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,47 @@
+use mines::{
+    game::{Action, Game, Status},
+    location::Loc,
+    mmap::MineMap,
+    svg::{self, Options},
+};
+
+#[test]
+fn render_lost_game() {
+    let mut map: MineMap = "* . .\n. . *".parse().unwrap();
+    // 插错的旗帜
+    map.switch_flag(2, 0);
+    let mut game = Game::by_map(map);
+    game.act(Action::Reveal(Loc(0, 0)));
+    assert_eq!(game.status(), Status::Lost);
+
+    let s = svg::render_game(&game, &Options::default());
+    assert!(s.starts_with("<svg") && s.ends_with("</svg>\n"));
+    assert_eq!(s.matches(r##"fill="#ff0000" stroke"##).count(), 1);
+    assert_eq!(s.matches(r##"stroke="#ff0000""##).count(), 1);
+    assert_eq!(s.matches("<circle").count(), 2);
+}
+
+#[test]
+fn render_labels_and_heat_map() {
+    let map: MineMap = "* .\n. .".parse().unwrap();
+    let probs = [0.25; 4];
+    let opts = Options {
+        labels: true,
+        probs: Some(&probs),
+        ..Default::default()
+    };
+    let s = svg::render(&map, &opts);
+    assert!(s.contains(r#"width="72" height="72""#));
+    assert_eq!(s.matches("<title>25.0%</title>").count(), 4);
+    assert_eq!(s.matches("<text").count(), 4);
+    // 完整布局不叠加热力图
+    let s = svg::render(
+        &map,
+        &Options {
+            layout: true,
+            ..opts
+        },
+    );
+    assert!(!s.contains("<title>"));
+    assert_eq!(s.matches("<circle").count(), 1);
+}